@@ -0,0 +1,213 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use veto_core::targets;
+
+/// One `<local ref> <local sha> <remote ref> <remote sha>` line git feeds a pre-push hook.
+#[derive(Debug, PartialEq)]
+struct PushedRef<'a> {
+    local_sha: &'a str,
+    remote_sha: &'a str,
+}
+
+fn parse_pre_push(input: &str) -> Result<Vec<PushedRef<'_>>> {
+    input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(
+            |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [_local_ref, local_sha, _remote_ref, remote_sha] => Ok(PushedRef {
+                    local_sha,
+                    remote_sha,
+                }),
+                _ => anyhow::bail!("unexpected pre-push input line: {:?}", line),
+            },
+        )
+        .collect()
+}
+
+/// One `<old> <new> <ref>` line git feeds a pre-receive hook.
+#[derive(Debug, PartialEq)]
+struct ReceivedRef<'a> {
+    new: &'a str,
+    name: &'a str,
+}
+
+fn parse_pre_receive(input: &str) -> Result<Vec<ReceivedRef<'_>>> {
+    input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(
+            |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [_old, new, name] => Ok(ReceivedRef { new, name }),
+                _ => anyhow::bail!("unexpected pre-receive input line: {:?}", line),
+            },
+        )
+        .collect()
+}
+
+/// Commits a push to this (usually bare) repository introduces, oldest first,
+/// and the names of the refs that bring them.
+///
+/// While pre-receive runs no ref has been updated yet, so anything not
+/// reachable from an existing ref is new. Deleted refs introduce nothing.
+pub fn pre_receive_commits(repo_root: &Path, input: &str) -> Result<(Vec<String>, Vec<String>)> {
+    let mut commits: Vec<String> = vec![];
+    let mut refs = vec![];
+    for received in parse_pre_receive(input)? {
+        if targets::is_null_oid(received.new) {
+            continue;
+        }
+        let new = targets::new_commits(repo_root, received.new, &["--all"])?;
+        if !new.is_empty() {
+            refs.push(received.name.to_string());
+        }
+        for commit in new {
+            if !commits.contains(&commit) {
+                commits.push(commit);
+            }
+        }
+    }
+    Ok((commits, refs))
+}
+
+/// Commits a push introduces, oldest first, across all pushed refs.
+///
+/// Deleted refs introduce nothing. For an updated ref, commits since the old
+/// remote tip are new; for a new ref (or a remote tip we don't have locally),
+/// everything not on a remote-tracking branch is.
+pub fn pre_push_commits(repo_root: &Path, input: &str) -> Result<Vec<String>> {
+    let mut commits: Vec<String> = vec![];
+    for pushed in parse_pre_push(input)? {
+        if targets::is_null_oid(pushed.local_sha) {
+            continue;
+        }
+        let exclude = if !targets::is_null_oid(pushed.remote_sha)
+            && targets::has_commit(repo_root, pushed.remote_sha)
+        {
+            pushed.remote_sha
+        } else {
+            "--remotes"
+        };
+        for commit in targets::new_commits(repo_root, pushed.local_sha, &[exclude])? {
+            if !commits.contains(&commit) {
+                commits.push(commit);
+            }
+        }
+    }
+    Ok(commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use veto_core::testutil::TestRepo;
+
+    /// A repository with `main` pushed to a bare `origin` (so `origin/main`
+    /// exists) and a local branch `feat` two commits ahead of it.
+    fn pushed_repo() -> (TestRepo, TestRepo, [String; 3]) {
+        let (work, origin) = (TestRepo::new(), TestRepo::bare());
+        let base = work.commit("base");
+        work.git(&["remote", "add", "origin", &origin.path().to_string_lossy()]);
+        work.git(&["push", "-q", "origin", "main"]);
+        work.git(&["checkout", "-q", "-b", "feat"]);
+        let first = work.commit("first");
+        let second = work.commit("second");
+        (work, origin, [base, first, second])
+    }
+
+    #[test]
+    fn test_pre_push_commits() {
+        let (work, _origin, [base, first, second]) = pushed_repo();
+        let zero = "0".repeat(40);
+        let push = |local: &str, remote: &str| {
+            let input = format!("refs/heads/feat {} refs/heads/feat {}\n", local, remote);
+            pre_push_commits(work.path(), &input).unwrap()
+        };
+
+        // New branch: everything not on a remote-tracking branch
+        assert_eq!(push(&second, &zero), vec![first.clone(), second.clone()]);
+        // Update: commits since the remote tip
+        assert_eq!(push(&second, &first), vec![second.clone()]);
+        // Deletion introduces nothing
+        assert!(push(&zero, &second).is_empty());
+
+        // Force-push over a remote tip we have: only the rewritten commits
+        work.git(&["reset", "-q", "--hard", &base]);
+        let rewritten = work.commit("rewritten");
+        assert_eq!(push(&rewritten, &second), vec![rewritten.clone()]);
+        // ... and over one we don't have: fall back to the remote-tracking branches
+        assert_eq!(push(&rewritten, &"1".repeat(40)), vec![rewritten]);
+    }
+
+    #[test]
+    fn test_pre_receive_commits() {
+        let (work, origin, [base, first, second]) = pushed_repo();
+        // The pushed objects are in the bare repository, but (as during
+        // pre-receive) no ref points at them yet
+        work.git(&["push", "-q", "origin", "feat"]);
+        origin.git(&["update-ref", "-d", "refs/heads/feat"]);
+        let zero = "0".repeat(40);
+        let receive = |input: String| pre_receive_commits(origin.path(), &input).unwrap();
+
+        // New branch: everything not reachable from an existing ref
+        assert_eq!(
+            receive(format!("{} {} refs/heads/feat\n", zero, second)),
+            (
+                vec![first.clone(), second.clone()],
+                vec!["refs/heads/feat".to_string()]
+            )
+        );
+        // Several refs bringing the same commits list them once
+        assert_eq!(
+            receive(format!(
+                "{z} {f} refs/heads/a\n{z} {s} refs/heads/b\n",
+                z = zero,
+                f = first,
+                s = second
+            )),
+            (
+                vec![first.clone(), second.clone()],
+                vec!["refs/heads/a".to_string(), "refs/heads/b".to_string()]
+            )
+        );
+        // Deletions and refs moved to commits the repository already has introduce nothing
+        assert_eq!(
+            receive(format!(
+                "{} {} refs/heads/main\n{} {} refs/tags/v1\n",
+                base, zero, zero, base
+            )),
+            (vec![], vec![])
+        );
+    }
+
+    #[test]
+    fn test_parse_pre_push() {
+        let zero = "0".repeat(40);
+        let input = format!(
+            "refs/heads/feat abc123 refs/heads/feat {}\n(delete) {} refs/heads/old def456\n",
+            zero, zero
+        );
+        let refs = parse_pre_push(&input).unwrap();
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].local_sha, "abc123");
+        assert!(targets::is_null_oid(refs[0].remote_sha));
+        assert!(targets::is_null_oid(refs[1].local_sha));
+        assert!(parse_pre_push("garbage\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_pre_receive() {
+        let zero = "0".repeat(40);
+        let input = format!("{} abc123 refs/heads/feat\n", zero);
+        assert_eq!(
+            parse_pre_receive(&input).unwrap(),
+            vec![ReceivedRef {
+                new: "abc123",
+                name: "refs/heads/feat"
+            }]
+        );
+        assert!(parse_pre_receive("a b\n").is_err());
+    }
+}
@@ -1,13 +1,34 @@
+mod hook;
+mod output;
+
+use std::io::{IsTerminal as _, Read as _, Write as _};
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use anyhow::{Context as _, Result};
+use clap::{Args, Parser, Subcommand};
+use sha2::{Digest, Sha256};
 
 use veto_config::Config;
-use veto_core::checks::entropy_guard::EntropyGuard;
+use veto_core::calibrate;
+use veto_core::checks::benign::{BenignAction, Builtin, Classifier};
+use veto_core::checks::cargo_policy::{CargoPolicy, CratesIndex};
+use veto_core::checks::commit_metadata::CommitMetadata;
+use veto_core::checks::entropy_guard::{CharsetHint, EntropyGuard, Thresholds};
+use veto_core::checks::forbidden_files::ForbiddenFiles;
+use veto_core::checks::large_files::LargeFiles;
+use veto_core::checks::npm_lock::NpmLock;
+use veto_core::checks::regex_rule::{self, RegexRule};
+use veto_core::examples::Examples;
+use veto_core::glob::PathGlobs;
+use veto_core::osv::OsvDb;
+use veto_core::redact::Redaction;
 use veto_core::runner::ScopeMode;
+use veto_core::targets;
 use veto_core::{Context, Runner, Severity};
 
+use crate::output::{Format, TextOptions};
+
 #[derive(Parser, Debug)]
 #[command(
     name = "veto",
@@ -31,30 +52,120 @@ struct Cli {
 enum Command {
     /// Run checks
     Scan {
-        /// Output format: text|json (overrides config)
-        #[arg(long)]
-        format: Option<String>,
+        /// Files or directories to scan instead of the git scope; `-` reads stdin
+        #[arg(value_name = "PATH")]
+        paths: Vec<PathBuf>,
+
+        /// Path reported for content read from stdin
+        #[arg(long, value_name = "NAME", default_value = "<stdin>")]
+        stdin_name: String,
 
         /// Scope: staged|worktree|repo (overrides config)
         #[arg(long)]
         scope: Option<String>,
 
-        /// Explain findings (verbose metadata)
-        #[arg(long)]
-        explain: bool,
+        /// Scan only what the head adds since its merge-base with this ref (e.g. a PR target branch)
+        #[arg(long, value_name = "REF")]
+        diff_base: Option<String>,
+
+        /// Head of the diff scanned with --diff-base
+        #[arg(
+            long,
+            value_name = "REF",
+            default_value = "HEAD",
+            requires = "diff_base"
+        )]
+        diff_head: String,
+
+        #[command(flatten)]
+        out: OutputArgs,
+    },
+
+    /// Run as a git hook
+    Hook {
+        #[command(subcommand)]
+        hook: Hook,
+    },
+
+    /// Suggest entropy_guard thresholds from a corpus (prints statistics only, never tokens)
+    Calibrate {
+        /// Files or directories assumed to hold no secrets (default: current dir)
+        #[arg(value_name = "PATH")]
+        paths: Vec<PathBuf>,
+
+        /// Files or directories of known secrets, one per line (repeatable)
+        #[arg(long, value_name = "PATH")]
+        secrets: Vec<PathBuf>,
+    },
+
+    /// Inspect and test rules
+    Rules {
+        #[command(subcommand)]
+        cmd: RulesCommand,
     },
 
     /// Print environment & basic diagnostics
     Doctor,
 }
 
+#[derive(Subcommand, Debug)]
+enum RulesCommand {
+    /// Run every rule's should_match / should_not_match examples
+    Test,
+}
+
+#[derive(Subcommand, Debug)]
+enum Hook {
+    /// pre-push: scan the commits being pushed (ref lines on stdin)
+    PrePush {
+        /// Remote name (passed by git)
+        remote: Option<String>,
+
+        /// Remote URL (passed by git)
+        url: Option<String>,
+
+        #[command(flatten)]
+        out: OutputArgs,
+    },
+
+    /// pre-receive (server side, bare repos): reject pushes that introduce findings
+    PreReceive {
+        #[command(flatten)]
+        out: OutputArgs,
+    },
+}
+
+/// How scan results are reported.
+#[derive(Args, Debug)]
+struct OutputArgs {
+    /// Output format: text|json|ndjson|sarif|junit|checkstyle|gitlab (overrides config)
+    #[arg(long)]
+    format: Option<String>,
+
+    /// Explain findings (verbose metadata)
+    #[arg(long)]
+    explain: bool,
+
+    /// Print only the summary line (text format)
+    #[arg(long, short)]
+    quiet: bool,
+
+    /// Write the --format output to this file instead of stdout
+    #[arg(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+
+    /// Additionally write a report in another format, e.g. `sarif=out.sarif` (repeatable)
+    #[arg(long = "report", value_name = "FORMAT=PATH")]
+    reports: Vec<String>,
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     let repo_root = cli.repo.unwrap_or(std::env::current_dir()?);
 
     // Load config (optional)
-    let cfg = load_config(cli.config.as_deref()).unwrap_or_default();
+    let cfg = with_env_overrides(load_config(cli.config.as_deref()).unwrap_or_default());
 
     match cli.cmd {
         Command::Doctor => {
@@ -71,53 +182,320 @@ fn main() -> Result<()> {
             println!("- rust: {}", env!("CARGO_PKG_RUST_VERSION"));
             Ok(())
         }
+        Command::Calibrate { paths, secrets } => calibrate(&cfg, repo_root, paths, secrets),
+        Command::Rules {
+            cmd: RulesCommand::Test,
+        } => {
+            let exit_code = rules_test(&cfg)?;
+            std::process::exit(exit_code);
+        }
         Command::Scan {
-            format,
+            paths,
+            stdin_name,
             scope,
-            explain,
+            diff_base,
+            diff_head,
+            out,
         } => {
-            let format = format
-                .or_else(|| Some(cfg.output.format.clone()))
-                .unwrap_or_else(|| "text".into());
-            let scope = scope
-                .or_else(|| Some(cfg.scope.mode.clone()))
-                .unwrap_or_else(|| "staged".into());
-
-            // ... context logic ...
-
-            let ctx = Context {
-                repo_root,
-                scope: parse_scope(&scope),
+            let scope = if !paths.is_empty() {
+                if scope.is_some() || diff_base.is_some() {
+                    anyhow::bail!("--scope/--diff-base cannot be combined with explicit paths");
+                }
+                path_scope(paths, stdin_name)?
+            } else if let Some(base) = diff_base {
+                if scope.is_some() {
+                    anyhow::bail!("--scope cannot be combined with --diff-base");
+                }
+                let head = targets::resolve_commit(&repo_root, &diff_head)?;
+                ScopeMode::Diff {
+                    base: targets::merge_base(&repo_root, &base, &head)?,
+                    head,
+                }
+            } else {
+                let scope = scope
+                    .or_else(|| Some(cfg.scope.mode.clone()))
+                    .unwrap_or_else(|| "staged".into());
+                parse_scope(&scope)
             };
 
-            // Runner with EntropyGuard
-            let entropy_guard = EntropyGuard {
-                enabled: cfg.entropy_guard.enabled,
-                min_length: cfg.entropy_guard.min_length,
-                threshold: cfg.entropy_guard.threshold,
-                ignore_extensions: cfg.entropy_guard.ignore_ext.clone(),
-                allowlist: cfg.allowlist.patterns.clone(),
-                max_file_bytes: cfg.entropy_guard.max_file_bytes,
-                max_line_length: cfg.entropy_guard.max_line_length,
-                max_tokens_per_file: cfg.entropy_guard.max_tokens_per_file,
-            };
-            let runner = Runner::new().with_check(Box::new(entropy_guard));
+            let exit_code = scan(&cfg, repo_root, scope, &out)?;
+            std::process::exit(exit_code);
+        }
+        Command::Hook {
+            hook: Hook::PrePush { remote, url, out },
+        } => {
+            let mut input = String::new();
+            std::io::stdin().lock().read_to_string(&mut input)?;
+            let commits = hook::pre_push_commits(&repo_root, &input)?;
+            let exit_code = scan(&cfg, repo_root, ScopeMode::Commits(commits), &out)?;
+            if exit_code != 0 {
+                eprintln!(
+                    "veto: push to {} blocked; remove the findings above from the pushed commits",
+                    remote.or(url).as_deref().unwrap_or("remote")
+                );
+            }
+            std::process::exit(exit_code);
+        }
+        Command::Hook {
+            hook: Hook::PreReceive { out },
+        } => {
+            // Policy comes from the server only: --config, or veto.toml in the
+            // hook's working directory (the repository's git dir). A policy that
+            // fails to load rejects the push instead of falling back to defaults.
+            let result = (|| -> Result<i32> {
+                let cfg = with_env_overrides(load_config(cli.config.as_deref())?);
+                let mut input = String::new();
+                std::io::stdin().lock().read_to_string(&mut input)?;
+                let (commits, refs) = hook::pre_receive_commits(&repo_root, &input)?;
+                let exit_code = scan(&cfg, repo_root, ScopeMode::Commits(commits), &out)?;
+                if exit_code != 0 {
+                    eprintln!(
+                        "veto: push rejected: {} introduces findings at or above `{}`",
+                        refs.join(", "),
+                        cfg.output.fail_on
+                    );
+                    eprintln!("veto: rewrite the commits to remove them; secrets that were pushed anywhere must be rotated");
+                }
+                Ok(exit_code)
+            })();
+            // Everything printed here is relayed to the pusher, so errors show
+            // only their outermost message (no server paths or config contents).
+            let exit_code = result.unwrap_or_else(|e| {
+                eprintln!("veto: push rejected: {}", e);
+                1
+            });
+            std::process::exit(exit_code);
+        }
+    }
+}
 
-            let report = runner.run(&ctx)?;
+/// Runs the configured checks over `scope`, writes the requested reports and
+/// returns the exit code.
+fn scan(cfg: &Config, repo_root: PathBuf, scope: ScopeMode, out: &OutputArgs) -> Result<i32> {
+    let format = out
+        .format
+        .clone()
+        .or_else(|| Some(cfg.output.format.clone()))
+        .unwrap_or_else(|| "text".into());
+    let format = Format::parse(&format)?;
+    let reports = out
+        .reports
+        .iter()
+        .map(|r| parse_report_arg(r))
+        .collect::<Result<Vec<_>>>()?;
 
-            let exit_code = exit_code_from(&cfg, report.worst_severity());
-            match format.as_str() {
-                "json" => {
-                    println!("{}", serde_json::to_string_pretty(&report)?);
-                }
-                _ => {
-                    print_text(&report, explain);
-                }
+    let ctx = scan_context(cfg, repo_root, scope)?;
+    let runner = build_runner(cfg)?;
+    let metadata = runner.metadata(&ctx, Some(config_hash(cfg)?));
+
+    let report = if format == Format::Ndjson && out.output.is_none() {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(output::ndjson_header(&metadata)?.as_bytes())?;
+        let report = runner.run_streaming(&ctx, |f| {
+            stdout.write_all(output::ndjson_finding(f)?.as_bytes())?;
+            Ok(stdout.flush()?)
+        })?;
+        stdout.write_all(output::ndjson_summary(&report)?.as_bytes())?;
+        report
+    } else {
+        let report = runner.run(&ctx)?;
+        let text = TextOptions {
+            explain: out.explain,
+            quiet: out.quiet,
+            color: out.output.is_none() && use_color(),
+        };
+        let rendered = output::render(&report, &metadata, format, &text)?;
+        match &out.output {
+            Some(path) => output::write_atomic(path, &rendered)?,
+            None => print!("{}", rendered),
+        }
+        report
+    };
+    let file_text = TextOptions {
+        explain: out.explain,
+        quiet: out.quiet,
+        color: false,
+    };
+    for (format, path) in &reports {
+        let rendered = output::render(&report, &metadata, *format, &file_text)?;
+        output::write_atomic(path, &rendered)?;
+    }
+
+    Ok(exit_code_from(cfg, report.worst_severity()))
+}
+
+/// Prints the entropy statistics of a corpus and a suggested `[entropy_guard]`
+/// block. Only aggregate numbers are printed, never token values.
+fn calibrate(
+    cfg: &Config,
+    repo_root: PathBuf,
+    paths: Vec<PathBuf>,
+    secrets: Vec<PathBuf>,
+) -> Result<()> {
+    let guard = entropy_guard(cfg)?;
+    let paths = if paths.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        paths
+    };
+    let ignore = ignore_globs(cfg)?;
+    let mut ctx = Context::new(repo_root.clone(), ScopeMode::Paths(paths));
+    ctx.ignore = ignore.clone();
+    let tokens = calibrate::token_samples(&ctx, &guard)?;
+    let files = ctx.coverage.take().files_scanned;
+    let secret_samples = if secrets.is_empty() {
+        vec![]
+    } else {
+        let mut ctx = Context::new(repo_root, ScopeMode::Paths(secrets.clone()));
+        ctx.ignore = ignore;
+        calibrate::secret_samples(&ctx, &guard)?
+    };
+
+    let min_length = calibrate::suggest_min_length(&secret_samples, guard.min_length);
+    let tokens: Vec<_> = tokens.into_iter().filter(|t| t.len >= min_length).collect();
+    let suggested = calibrate::suggest_thresholds(&tokens, &secret_samples, guard.thresholds);
+
+    print!(
+        "Corpus: {} file(s), {} candidate token(s) of length >= {}",
+        files,
+        tokens.len(),
+        min_length
+    );
+    if secrets.is_empty() {
+        println!("; no known secrets (pass --secrets to measure detection)");
+    } else {
+        println!("; {} known secret(s)", secret_samples.len());
+    }
+
+    println!("\nEntropy by charset (bits/char):");
+    println!(
+        "{:<10} {:>7} {:>6} {:>6} {:>6} {:>6} {:>6}",
+        "charset", "count", "min", "p50", "p90", "p99", "max"
+    );
+    for s in calibrate::distribution(&tokens) {
+        println!(
+            "{:<10} {:>7} {:>6.2} {:>6.2} {:>6.2} {:>6.2} {:>6.2}",
+            s.charset.key(),
+            s.count,
+            s.min,
+            s.p50,
+            s.p90,
+            s.p99,
+            s.max
+        );
+    }
+
+    let header = || {
+        print!("{:<10}", "charset");
+        for t in calibrate::CANDIDATE_THRESHOLDS {
+            print!(" {:>6.2}", t);
+        }
+        println!();
+    };
+    println!("\nFalse positives per charset threshold:");
+    header();
+    for charset in CharsetHint::ALL {
+        print!("{:<10}", charset.key());
+        for t in calibrate::CANDIDATE_THRESHOLDS {
+            let count = tokens
+                .iter()
+                .filter(|x| x.charset == charset && x.flagged_at(t))
+                .count();
+            print!(" {:>6}", count);
+        }
+        println!();
+    }
+
+    if !secrets.is_empty() {
+        println!("\nKnown secrets found per charset threshold:");
+        header();
+        for charset in CharsetHint::ALL {
+            let known: Vec<_> = secret_samples
+                .iter()
+                .flatten()
+                .filter(|x| x.charset == charset)
+                .collect();
+            if known.is_empty() {
+                continue;
+            }
+            print!("{:<10}", charset.key());
+            for t in calibrate::CANDIDATE_THRESHOLDS {
+                let found = known.iter().filter(|x| x.flagged_at(t)).count();
+                print!(" {:>6}", format!("{}/{}", found, known.len()));
             }
+            println!();
+        }
+    }
 
-            std::process::exit(exit_code);
+    let unreachable = secret_samples.iter().filter(|s| s.is_none()).count();
+    if unreachable > 0 {
+        println!(
+            "\n{} known secret(s) have no token entropy can judge (shorter than {} chars, allowlisted or too uniform)",
+            unreachable,
+            calibrate::MIN_LENGTH_FLOOR
+        );
+    }
+
+    println!("\nSuggested config:");
+    println!("[entropy_guard]");
+    println!("min_length = {}", min_length);
+    println!("\n[entropy_guard.thresholds]");
+    for charset in CharsetHint::ALL {
+        println!("{} = {:?}", charset.key(), suggested.get(charset));
+    }
+    if guard.normalized_threshold.is_some() {
+        println!("\nNote: normalized_threshold is set, so these thresholds only apply once it is removed.");
+    }
+    Ok(())
+}
+
+/// Runs the examples of every check and prints one line per check, plus one
+/// per failing example (redacted). Returns 1 if any example fails.
+fn rules_test(cfg: &Config) -> Result<i32> {
+    let redaction = parse_redaction(&cfg.output.redaction)?;
+    let reports = build_runner(cfg)?.test_examples();
+    let width = reports.iter().map(|r| r.check_id.len()).max().unwrap_or(0);
+
+    let mut failed = 0;
+    for report in &reports {
+        if report.failures.is_empty() {
+            println!(
+                "{:width$}  ok      {} example(s)",
+                report.check_id, report.total
+            );
+            continue;
+        }
+        failed += report.failures.len();
+        println!(
+            "{:width$}  FAILED  {} of {} example(s)",
+            report.check_id,
+            report.failures.len(),
+            report.total
+        );
+        for failure in &report.failures {
+            let (kind, outcome) = if failure.should_match {
+                ("should_match", "did not fire")
+            } else {
+                ("should_not_match", "fired")
+            };
+            println!(
+                "{:width$}    {} {}: {}",
+                "",
+                kind,
+                outcome,
+                redaction.redact(&failure.sample)
+            );
         }
     }
+
+    let total: usize = reports.iter().map(|r| r.total).sum();
+    if failed > 0 {
+        println!("\n{} of {} example(s) failed", failed, total);
+        Ok(1)
+    } else {
+        println!("\nAll {} example(s) passed", total);
+        Ok(0)
+    }
 }
 
 fn load_config(override_path: Option<&std::path::Path>) -> Result<Config> {
@@ -146,6 +524,277 @@ fn load_config(override_path: Option<&std::path::Path>) -> Result<Config> {
     }
 }
 
+/// Applies the settings that may come from the environment instead of the
+/// config file, so secrets need not be committed.
+fn with_env_overrides(mut cfg: Config) -> Config {
+    if let Ok(key) = std::env::var("VETO_FINGERPRINT_KEY") {
+        cfg.output.fingerprint_key = key;
+    }
+    if let Ok(salt) = std::env::var("VETO_REDACTION_SALT") {
+        cfg.output.redaction.salt = salt;
+    }
+    cfg
+}
+
+/// SHA-256 of the effective configuration (file values, defaults and
+/// environment overrides). The hash is published in every report, so secrets
+/// only contribute whether they are set, never their value.
+fn config_hash(cfg: &Config) -> Result<String> {
+    let mut cfg = cfg.clone();
+    for secret in [
+        &mut cfg.output.fingerprint_key,
+        &mut cfg.output.redaction.salt,
+    ] {
+        if !secret.is_empty() {
+            *secret = "<secret>".to_string();
+        }
+    }
+    let digest = Sha256::digest(serde_json::to_vec(&cfg)?);
+    Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn build_runner(cfg: &Config) -> Result<Runner> {
+    let forbidden_files = ForbiddenFiles {
+        enabled: cfg.forbidden_files.enabled,
+        patterns: PathGlobs::new(&cfg.forbidden_files.patterns)?,
+        allow: PathGlobs::new(&cfg.forbidden_files.allow)?,
+        severity: parse_severity(&cfg.forbidden_files.severity),
+        examples: examples(
+            &cfg.forbidden_files.should_match,
+            &cfg.forbidden_files.should_not_match,
+        ),
+    };
+    let large_files = LargeFiles {
+        enabled: cfg.large_files.enabled,
+        max_bytes: cfg.large_files.max_bytes,
+        binary_allow: PathGlobs::new(&cfg.large_files.binary_allow)?,
+        oversize: parse_rule_severity(&cfg.large_files.severity.oversize),
+        binary: parse_rule_severity(&cfg.large_files.severity.binary),
+        lfs: parse_rule_severity(&cfg.large_files.severity.lfs),
+    };
+    let commits = &cfg.commits;
+    let commit_metadata = CommitMetadata {
+        enabled: commits.enabled,
+        entropy: entropy_guard(cfg)?,
+        deny_patterns: commits
+            .deny_patterns
+            .iter()
+            .map(|p| compile_match_regex("commits.deny_patterns", p))
+            .collect::<Result<_>>()?,
+        email_domains: commits.email_domains.clone(),
+        message_pattern: match commits.message_pattern.as_str() {
+            "" => None,
+            p => Some(compile_regex("commits.message_pattern", p)?),
+        },
+        secret: parse_rule_severity(&commits.severity.secret),
+        email: parse_rule_severity(&commits.severity.email),
+        format: parse_rule_severity(&commits.severity.format),
+    };
+    let npm = NpmLock {
+        enabled: cfg.npm.enabled,
+        osv: match cfg.npm.osv_db.as_str() {
+            "" => None,
+            dir => Some(OsvDb::load(std::path::Path::new(dir)).context("npm.osv_db")?),
+        },
+        registries: cfg.npm.registries.clone(),
+        advisory: parse_rule_severity(&cfg.npm.severity.advisory),
+        registry: parse_rule_severity(&cfg.npm.severity.registry),
+        insecure_url: parse_rule_severity(&cfg.npm.severity.insecure_url),
+        integrity: parse_rule_severity(&cfg.npm.severity.integrity),
+    };
+    let cargo = CargoPolicy {
+        enabled: cfg.cargo.enabled,
+        registries: cfg.cargo.registries.clone(),
+        banned: cfg.cargo.banned.clone(),
+        licenses: cfg.cargo.licenses.clone(),
+        index: match cfg.cargo.index.as_str() {
+            "" => None,
+            dir if !std::path::Path::new(dir).is_dir() => {
+                anyhow::bail!("cargo.index {:?} is not a directory", dir)
+            }
+            dir => Some(CratesIndex { root: dir.into() }),
+        },
+        source: parse_rule_severity(&cfg.cargo.severity.source),
+        git_rev: parse_rule_severity(&cfg.cargo.severity.git_rev),
+        banned_severity: parse_rule_severity(&cfg.cargo.severity.banned),
+        yanked: parse_rule_severity(&cfg.cargo.severity.yanked),
+        license: parse_rule_severity(&cfg.cargo.severity.license),
+    };
+
+    let mut runner = Runner::new()
+        .with_check(Box::new(forbidden_files))
+        .with_check(Box::new(large_files))
+        .with_check(Box::new(entropy_guard(cfg)?))
+        .with_check(Box::new(commit_metadata))
+        .with_check(Box::new(npm))
+        .with_check(Box::new(cargo))
+        .with_skips_as_findings(parse_rule_severity(&cfg.output.skips_as_findings));
+    for rule in build_rules(cfg)? {
+        runner = runner.with_check(Box::new(rule));
+    }
+    Ok(runner)
+}
+
+/// Compiles `[[rules]]`, dropping rules whose severity is "off".
+fn build_rules(cfg: &Config) -> Result<Vec<RegexRule>> {
+    let mut ids = vec![
+        "FF-001",
+        "LF-001",
+        "EG-001",
+        "CM-001",
+        "NPM-001",
+        "CARGO-001",
+        "COV-001",
+    ];
+    let mut rules = vec![];
+    for rule in &cfg.rules {
+        if rule.id.trim().is_empty() {
+            anyhow::bail!("[[rules]] entry with an empty id");
+        }
+        if ids.contains(&rule.id.as_str()) {
+            anyhow::bail!("duplicate rule id {:?}", rule.id);
+        }
+        ids.push(&rule.id);
+
+        let key = format!("rules.{}", rule.id);
+        let Some(severity) = parse_rule_severity(&rule.severity) else {
+            continue;
+        };
+        rules.push(RegexRule {
+            id: rule.id.clone(),
+            title: rule.title.clone(),
+            regex: compile_match_regex(&key, &rule.regex)?,
+            severity,
+            paths: PathGlobs::new(&rule.paths)?,
+            exclude_paths: PathGlobs::new(&rule.exclude_paths)?,
+            min_entropy: rule.min_entropy,
+            allowlist: rule
+                .allowlist
+                .iter()
+                .map(|a| compile_regex(&format!("{}.allowlist", key), a))
+                .collect::<Result<_>>()?,
+            max_file_bytes: cfg.entropy_guard.max_file_bytes,
+            max_line_length: cfg.entropy_guard.max_line_length,
+            snippet_context: cfg.entropy_guard.snippet_context,
+            examples: examples(&rule.should_match, &rule.should_not_match),
+        });
+    }
+    Ok(rules)
+}
+
+fn entropy_guard(cfg: &Config) -> Result<EntropyGuard> {
+    Ok(EntropyGuard {
+        enabled: cfg.entropy_guard.enabled,
+        min_length: cfg.entropy_guard.min_length,
+        thresholds: thresholds(&cfg.entropy_guard),
+        normalized_threshold: cfg.entropy_guard.normalized_threshold,
+        classifiers: classifiers(&cfg.entropy_guard.benign)?,
+        allowlist: cfg.allowlist.patterns.clone(),
+        max_file_bytes: cfg.entropy_guard.max_file_bytes,
+        max_line_length: cfg.entropy_guard.max_line_length,
+        max_tokens_per_file: cfg.entropy_guard.max_tokens_per_file,
+        snippet_context: cfg.entropy_guard.snippet_context,
+        examples: examples(
+            &cfg.entropy_guard.should_match,
+            &cfg.entropy_guard.should_not_match,
+        ),
+    })
+}
+
+/// `Config::ignore_paths`, warning about the deprecated `entropy_guard.ignore_ext`.
+fn ignore_globs(cfg: &Config) -> Result<PathGlobs> {
+    if cfg.entropy_guard.ignore_ext.is_some() {
+        eprintln!("veto: entropy_guard.ignore_ext is deprecated; use [ignore] paths (e.g. \"*.png\") instead");
+    }
+    PathGlobs::new(&cfg.ignore_paths()).context("invalid ignore.paths")
+}
+
+/// The built-in benign classifiers not turned "off", in `Builtin::ALL` order.
+fn classifiers(
+    cfg: &veto_config::BenignConfig,
+) -> Result<Vec<(Arc<dyn Classifier>, BenignAction)>> {
+    let actions = [
+        &cfg.git_sha,
+        &cfg.uuid,
+        &cfg.sri,
+        &cfg.go_sum,
+        &cfg.nix_store,
+        &cfg.data_uri,
+    ];
+    let mut classifiers = vec![];
+    for (builtin, action) in Builtin::ALL.into_iter().zip(actions) {
+        let action = match action.as_str() {
+            "drop" => BenignAction::Drop,
+            "demote" => BenignAction::Demote,
+            "off" => continue,
+            other => anyhow::bail!(
+                "unknown entropy_guard.benign.{} {:?} (expected drop|demote|off)",
+                builtin.name(),
+                other
+            ),
+        };
+        classifiers.push((builtin.shared(), action));
+    }
+    Ok(classifiers)
+}
+
+fn thresholds(cfg: &veto_config::EntropyGuardConfig) -> Thresholds {
+    let base = Thresholds::from_base(cfg.threshold);
+    let per_charset = &cfg.thresholds;
+    Thresholds {
+        hex: per_charset.hex.unwrap_or(base.hex),
+        base64: per_charset.base64.unwrap_or(base.base64),
+        base64url: per_charset.base64url.unwrap_or(base.base64url),
+        alnum: per_charset.alnum.unwrap_or(base.alnum),
+    }
+}
+
+fn examples(should_match: &[String], should_not_match: &[String]) -> Examples {
+    Examples {
+        should_match: should_match.to_vec(),
+        should_not_match: should_not_match.to_vec(),
+    }
+}
+
+fn compile_regex(key: &str, pattern: &str) -> Result<regex::Regex> {
+    regex::Regex::new(pattern).with_context(|| format!("invalid {} regex {:?}", key, pattern))
+}
+
+/// Like `compile_regex`, for a regex whose matches are reported: one that can
+/// match an empty string would report a zero-length finding on every line.
+fn compile_match_regex(key: &str, pattern: &str) -> Result<regex::Regex> {
+    let regex = compile_regex(key, pattern)?;
+    if regex_rule::matches_empty(&regex) {
+        anyhow::bail!("{} regex {:?} can match an empty string", key, pattern);
+    }
+    Ok(regex)
+}
+
+/// Colour only on a terminal, and never when `NO_COLOR` is set (https://no-color.org).
+fn use_color() -> bool {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    !no_color && std::io::stdout().is_terminal()
+}
+
+/// The scan context `cfg` configures for `scope`.
+fn scan_context(cfg: &Config, repo_root: PathBuf, scope: ScopeMode) -> Result<Context> {
+    let mut ctx = Context::new(repo_root, scope);
+    if !cfg.output.fingerprint_key.is_empty() {
+        ctx.fingerprint_key = cfg.output.fingerprint_key.clone().into_bytes();
+    }
+    ctx.redaction = parse_redaction(&cfg.output.redaction)?;
+    ctx.ignore = ignore_globs(cfg)?;
+    Ok(ctx)
+}
+
+/// Parses `--report FORMAT=PATH`.
+fn parse_report_arg(arg: &str) -> Result<(Format, PathBuf)> {
+    match arg.split_once('=') {
+        Some((format, path)) if !path.is_empty() => Ok((Format::parse(format)?, path.into())),
+        _ => anyhow::bail!("invalid --report {:?} (expected FORMAT=PATH)", arg),
+    }
+}
+
 fn parse_scope(s: &str) -> ScopeMode {
     match s {
         "repo" => ScopeMode::Repo,
@@ -154,13 +803,57 @@ fn parse_scope(s: &str) -> ScopeMode {
     }
 }
 
-fn exit_code_from(cfg: &Config, worst: Option<Severity>) -> i32 {
-    let threshold = match cfg.output.fail_on.as_str() {
+/// Scope for positional `scan` arguments: `-` alone reads stdin, anything else is walked on disk.
+fn path_scope(paths: Vec<PathBuf>, stdin_name: String) -> Result<ScopeMode> {
+    let stdin = paths.iter().any(|p| p.as_os_str() == "-");
+    if !stdin {
+        return Ok(ScopeMode::Paths(paths));
+    }
+    if paths.len() > 1 {
+        anyhow::bail!("`-` (stdin) cannot be combined with other paths");
+    }
+    let mut content = Vec::new();
+    std::io::stdin().lock().read_to_end(&mut content)?;
+    Ok(ScopeMode::Stdin {
+        name: stdin_name,
+        content: content.into(),
+    })
+}
+
+fn parse_redaction(cfg: &veto_config::RedactionConfig) -> Result<Redaction> {
+    Ok(match cfg.mode.as_str() {
+        "partial" => Redaction::Partial,
+        "full" => Redaction::Full,
+        "hash" => Redaction::Hash {
+            salt: cfg.salt.clone(),
+        },
+        "length" => Redaction::Length,
+        other => anyhow::bail!(
+            "unknown output.redaction.mode {:?} (expected partial|full|hash|length)",
+            other
+        ),
+    })
+}
+
+fn parse_severity(s: &str) -> Severity {
+    match s {
         "critical" => Severity::Critical,
         "high" => Severity::High,
         "medium" => Severity::Medium,
         _ => Severity::Low,
-    };
+    }
+}
+
+/// Like `parse_severity`, but `"off"` disables the rule.
+fn parse_rule_severity(s: &str) -> Option<Severity> {
+    match s {
+        "off" => None,
+        _ => Some(parse_severity(s)),
+    }
+}
+
+fn exit_code_from(cfg: &Config, worst: Option<Severity>) -> i32 {
+    let threshold = parse_severity(&cfg.output.fail_on);
 
     match worst {
         None => 0,
@@ -169,37 +862,89 @@ fn exit_code_from(cfg: &Config, worst: Option<Severity>) -> i32 {
     }
 }
 
-fn print_text(report: &veto_core::Report, explain: bool) {
-    if report.findings.is_empty() {
-        println!("OK (no findings) — {}ms", report.duration_ms);
-        return;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_hash_hides_secrets() {
+        let hash = |key: &str, salt: &str| {
+            let mut cfg = Config::default();
+            cfg.output.fingerprint_key = key.to_string();
+            cfg.output.redaction.salt = salt.to_string();
+            config_hash(&cfg).unwrap()
+        };
+        assert_eq!(hash("key-one", "salt-one"), hash("key-two", "salt-two"));
+        assert_ne!(hash("key-one", ""), hash("", ""));
+        assert_ne!(hash("", "salt-one"), hash("", ""));
     }
 
-    println!(
-        "Found {} issue(s) — {}ms",
-        report.findings.len(),
-        report.duration_ms
-    );
-    for f in &report.findings {
-        let loc = f
-            .location
-            .as_ref()
-            .map(|l| format!("{}:{}", l.file, l.line.unwrap_or(0)))
-            .unwrap_or_else(|| "-".into());
-        println!(
-            "- [{}] {} @ {}",
-            format!("{:?}", f.severity).to_uppercase(),
-            f.title,
-            loc
-        );
-        println!("  {}", f.message);
-        if explain {
-            if let Some(d) = &f.details {
-                println!(
-                    "  [Explain] entropy={:.2}, len={}, charset={}",
-                    d.entropy, d.token_len, d.charset
-                );
-            }
+    #[test]
+    fn test_absolute_paths_in_repo_are_ignored() {
+        // `veto scan $PWD/Cargo.lock $PWD/generated/data.txt` from the repo root
+        let dir = tempfile::tempdir().unwrap();
+        let token = "value = \"Zk9xQ2bR7vLp3TnW8yHs4JdK6mXa1cEf\"\n";
+        std::fs::create_dir_all(dir.path().join("generated")).unwrap();
+        let paths = vec![
+            dir.path().join("Cargo.lock"),
+            dir.path().join("generated/data.txt"),
+        ];
+        for path in &paths {
+            std::fs::write(path, token).unwrap();
         }
+        let mut cfg = Config::default();
+        cfg.ignore.paths.push("generated/*.txt".into());
+        let scope = path_scope(paths, "<stdin>".into()).unwrap();
+        let ctx = scan_context(&cfg, dir.path().to_path_buf(), scope).unwrap();
+
+        let all: Vec<String> = targets::list_all_targets(&ctx)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.path)
+            .collect();
+        assert_eq!(all, vec!["Cargo.lock", "generated/data.txt"]);
+        assert!(targets::list_targets(&ctx).unwrap().is_empty());
+        let report = build_runner(&cfg).unwrap().run(&ctx).unwrap();
+        assert!(report.findings.is_empty(), "{:?}", report.findings);
+    }
+
+    #[test]
+    fn test_default_config_skips_media() {
+        // Binary without a NUL byte in the sniff window, so it would decode as Latin-1
+        let dir = tempfile::tempdir().unwrap();
+        let logo = dir.path().join("logo.png");
+        std::fs::write(
+            &logo,
+            b"\x89PNG\r\n\x1a\nZk9xQ2bR7vLp3TnW8yHs4JdK6mXa1cEf\xff\xfe",
+        )
+        .unwrap();
+        let cfg = Config::default();
+        let scope = path_scope(vec![logo], "<stdin>".into()).unwrap();
+        let ctx = scan_context(&cfg, dir.path().to_path_buf(), scope).unwrap();
+        let report = build_runner(&cfg).unwrap().run(&ctx).unwrap();
+        assert!(report.findings.is_empty(), "{:?}", report.findings);
+        assert_eq!(report.coverage.files_scanned, 0);
+    }
+
+    #[test]
+    fn test_rules_rejecting_empty_matches() {
+        let mut cfg = Config::default();
+        cfg.rules.push(veto_config::RuleConfig {
+            id: "TODO-001".into(),
+            title: "Security TODO".into(),
+            regex: r"TODO\(security\)".into(),
+            severity: "low".into(),
+            paths: vec![],
+            exclude_paths: vec![],
+            min_entropy: None,
+            allowlist: vec![],
+            should_match: vec![],
+            should_not_match: vec![],
+        });
+        assert_eq!(build_rules(&cfg).unwrap().len(), 1);
+
+        cfg.rules[0].regex = r"(TODO)?".into();
+        let err = build_rules(&cfg).err().unwrap().to_string();
+        assert!(err.contains("can match an empty string"), "{}", err);
     }
 }
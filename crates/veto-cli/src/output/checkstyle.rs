@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use veto_core::{Finding, Report, Severity};
+
+use super::xml_escape;
+
+/// Checkstyle XML, grouped by file. Findings without a location are listed under `-`.
+pub fn render(report: &Report) -> String {
+    let mut by_file: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+    for f in &report.findings {
+        let file = f.location.as_ref().map(|l| l.file.as_str()).unwrap_or("-");
+        by_file.entry(file).or_default().push(f);
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(out, r#"<checkstyle version="4.3">"#);
+    for (file, findings) in by_file {
+        let _ = writeln!(out, r#"  <file name="{}">"#, xml_escape(file));
+        for f in findings {
+            let loc = f.location.as_ref();
+            let line = loc.and_then(|l| l.line).unwrap_or(0);
+            let column = loc
+                .and_then(|l| l.column)
+                .map(|c| format!(r#" column="{}""#, c))
+                .unwrap_or_default();
+            let _ = writeln!(
+                out,
+                r#"    <error line="{}"{} severity="{}" message="{}" source="veto.{}"/>"#,
+                line,
+                column,
+                severity(f.severity),
+                xml_escape(&format!("{}: {}", f.title, f.message)),
+                xml_escape(&f.id)
+            );
+        }
+        let _ = writeln!(out, "  </file>");
+    }
+    let _ = writeln!(out, "</checkstyle>");
+    out
+}
+
+fn severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low => "info",
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use veto_core::{Report, Severity};
+
+/// GitLab Code Quality report (a JSON array of issues, CodeClimate subset).
+pub fn render(report: &Report) -> Result<String> {
+    let issues: Vec<Value> = report
+        .findings
+        .iter()
+        .map(|f| {
+            let loc = f.location.as_ref();
+            json!({
+                "description": format!("{}: {}", f.title, f.message),
+                "check_name": f.id,
+                "fingerprint": f.fingerprint,
+                "severity": severity(f.severity),
+                "location": {
+                    "path": loc.map(|l| l.file.as_str()).unwrap_or("-"),
+                    "lines": { "begin": loc.and_then(|l| l.line).unwrap_or(1) },
+                },
+            })
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&issues)? + "\n")
+}
+
+fn severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "blocker",
+        Severity::High => "critical",
+        Severity::Medium => "major",
+        Severity::Low => "minor",
+    }
+}
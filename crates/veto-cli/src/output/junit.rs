@@ -0,0 +1,77 @@
+use std::fmt::Write as _;
+
+use veto_core::{Finding, Report};
+
+use super::{report_checks, severity_name, xml_escape};
+
+/// JUnit XML: one `<testsuite>` per check, one failing `<testcase>` per finding.
+/// A check without findings gets a single passing test case.
+pub fn render(report: &Report) -> String {
+    let checks = report_checks(report);
+    let findings_of =
+        |id: &str| -> Vec<&Finding> { report.findings.iter().filter(|f| f.id == id).collect() };
+    let mut out = String::new();
+    let _ = writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        out,
+        r#"<testsuites name="veto" tests="{}" failures="{}" time="{}">"#,
+        checks
+            .iter()
+            .map(|c| findings_of(&c.id).len().max(1))
+            .sum::<usize>(),
+        report.findings.len(),
+        seconds(report.duration_ms)
+    );
+
+    for check in &checks {
+        let findings = findings_of(&check.id);
+        let _ = writeln!(
+            out,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" time="{}">"#,
+            xml_escape(&check.id),
+            findings.len().max(1),
+            findings.len(),
+            seconds(check.duration_ms)
+        );
+        if findings.is_empty() {
+            let _ = writeln!(
+                out,
+                r#"    <testcase classname="{}" name="{}"/>"#,
+                xml_escape(&check.id),
+                xml_escape(&check.description)
+            );
+        }
+        for f in findings {
+            let loc = f
+                .location
+                .as_ref()
+                .map(|l| l.display())
+                .unwrap_or_else(|| "-".into());
+            let _ = writeln!(
+                out,
+                r#"    <testcase classname="{}" name="{}">"#,
+                xml_escape(&check.id),
+                xml_escape(&format!("{} @ {}", f.title, loc))
+            );
+            let _ = writeln!(
+                out,
+                r#"      <failure message="{}" type="{}">{}</failure>"#,
+                xml_escape(&f.title),
+                severity_name(f.severity),
+                xml_escape(&format!(
+                    "{}\n{}\nfingerprint: {}",
+                    loc, f.message, f.fingerprint
+                ))
+            );
+            let _ = writeln!(out, "    </testcase>");
+        }
+        let _ = writeln!(out, "  </testsuite>");
+    }
+
+    let _ = writeln!(out, "</testsuites>");
+    out
+}
+
+fn seconds(ms: u128) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
@@ -0,0 +1,336 @@
+mod checkstyle;
+mod gitlab;
+mod junit;
+mod sarif;
+mod text;
+
+use std::fs;
+use std::io::Write as _;
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+
+use veto_core::model::{
+    CheckTiming, ReportEnvelope, ScanMetadata, StreamRecord, REPORT_SCHEMA_VERSION,
+};
+use veto_core::{Finding, Report, Severity};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Ndjson,
+    Sarif,
+    Junit,
+    Checkstyle,
+    Gitlab,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(match s {
+            "text" => Format::Text,
+            "json" => Format::Json,
+            "ndjson" => Format::Ndjson,
+            "sarif" => Format::Sarif,
+            "junit" => Format::Junit,
+            "checkstyle" => Format::Checkstyle,
+            "gitlab" => Format::Gitlab,
+            other => anyhow::bail!(
+                "unknown format {:?} (expected text|json|ndjson|sarif|junit|checkstyle|gitlab)",
+                other
+            ),
+        })
+    }
+}
+
+/// Options for the human-readable `text` format.
+#[derive(Debug, Clone, Default)]
+pub struct TextOptions {
+    /// Verbose metadata per finding.
+    pub explain: bool,
+    /// ANSI colours (only when writing to a terminal).
+    pub color: bool,
+    /// Print only the summary line.
+    pub quiet: bool,
+}
+
+/// Renders a report. Findings are already redacted by `veto-core`, so every
+/// format only ever sees masked values.
+pub fn render(
+    report: &Report,
+    metadata: &ScanMetadata,
+    format: Format,
+    text: &TextOptions,
+) -> Result<String> {
+    Ok(match format {
+        Format::Text => text::render(report, text),
+        Format::Json => {
+            let envelope = ReportEnvelope::new(metadata.clone(), report.clone());
+            serde_json::to_string_pretty(&envelope)? + "\n"
+        }
+        Format::Ndjson => {
+            let mut out = ndjson_header(metadata)?;
+            for f in &report.findings {
+                out += &ndjson_finding(f)?;
+            }
+            out + &ndjson_summary(report)?
+        }
+        Format::Sarif => sarif::render(report)?,
+        Format::Junit => junit::render(report),
+        Format::Checkstyle => checkstyle::render(report),
+        Format::Gitlab => gitlab::render(report)?,
+    })
+}
+
+// NDJSON is emitted record by record (see `StreamRecord`), so a consumer can
+// process findings while the scan is still running.
+
+pub fn ndjson_header(metadata: &ScanMetadata) -> Result<String> {
+    ndjson_line(&StreamRecord::Header {
+        schema_version: REPORT_SCHEMA_VERSION.to_string(),
+        metadata: metadata.clone(),
+    })
+}
+
+pub fn ndjson_finding(finding: &Finding) -> Result<String> {
+    ndjson_line(&StreamRecord::Finding(finding.clone()))
+}
+
+pub fn ndjson_summary(report: &Report) -> Result<String> {
+    ndjson_line(&StreamRecord::Summary {
+        duration_ms: report.duration_ms,
+        coverage: report.coverage.clone(),
+    })
+}
+
+fn ndjson_line(record: &StreamRecord) -> Result<String> {
+    Ok(serde_json::to_string(record)? + "\n")
+}
+
+/// Writes `contents` to `path` via a temporary file in the same directory and a
+/// rename, so readers (e.g. a CI upload step) never see a partial report.
+pub fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let dir = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("invalid output path: {}", path.display()))?;
+    let tmp = dir.join(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let result = (|| -> Result<()> {
+        fs::create_dir_all(dir)?;
+        let mut f = fs::File::create(&tmp)?;
+        f.write_all(contents.as_bytes())?;
+        f.sync_all()?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result.with_context(|| format!("failed to write report: {}", path.display()))
+}
+
+/// Every check a report accounts for: those that ran, then ids that only
+/// appear in findings (e.g. `COV-001` for skipped files, added by the runner),
+/// described by their first finding's title.
+fn report_checks(report: &Report) -> Vec<CheckTiming> {
+    let mut checks = report.coverage.checks.clone();
+    for f in &report.findings {
+        if !checks.iter().any(|c| c.id == f.id) {
+            checks.push(CheckTiming {
+                id: f.id.clone(),
+                description: f.title.clone(),
+                duration_ms: 0,
+                findings: report.findings.iter().filter(|g| g.id == f.id).count(),
+            });
+        }
+    }
+    checks
+}
+
+fn severity_name(severity: Severity) -> String {
+    format!("{:?}", severity).to_uppercase()
+}
+
+/// Escapes text for XML attributes and content, dropping characters XML 1.0 cannot represent.
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' => out.push_str("&#10;"),
+            '\t' | '\r' => out.push(' '),
+            c if (c as u32) < 0x20 || c == '\u{FFFE}' || c == '\u{FFFF}' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use veto_core::model::{Coverage, DecodedFile, Encoding, Location};
+
+    /// Two entropy findings (one in a Latin-1 file) and a skipped file,
+    /// reported as `COV-001` by the runner rather than by a check.
+    fn sample_report() -> Report {
+        let finding = |id: &str, file: &str, severity, title: &str, message: &str| Finding {
+            id: id.to_string(),
+            fingerprint: format!("fp-{}", file),
+            title: title.to_string(),
+            message: message.to_string(),
+            severity,
+            location: Some(Location::file(file)),
+            tags: vec!["entropy".to_string()],
+            snippet: None,
+            details: None,
+        };
+        let mut token = finding(
+            "EG-001",
+            "src/a b.rs",
+            Severity::High,
+            "High entropy string",
+            "Token <x> looks random",
+        );
+        token.location = Some(Location {
+            line: Some(3),
+            column: Some(5),
+            end_line: Some(3),
+            end_column: Some(9),
+            start_byte: Some(20),
+            end_byte: Some(24),
+            ..Location::file("src/a b.rs")
+        });
+        let mut latin1 = finding(
+            "EG-001",
+            "docs/notes.txt",
+            Severity::Medium,
+            "High entropy string",
+            "Token looks random",
+        );
+        latin1.location = Some(Location {
+            line: Some(1),
+            column: Some(1),
+            start_byte: Some(0),
+            end_byte: Some(4),
+            ..Location::file("docs/notes.txt")
+        });
+        let mut skipped = finding(
+            "COV-001",
+            "big.bin",
+            Severity::Low,
+            "File not scanned",
+            "File was skipped (too-large)",
+        );
+        skipped.tags = vec!["coverage".to_string()];
+
+        Report {
+            findings: vec![token, latin1, skipped],
+            duration_ms: 1500,
+            coverage: Coverage {
+                files_scanned: 2,
+                files_decoded: vec![DecodedFile {
+                    file: "docs/notes.txt".to_string(),
+                    encoding: Encoding::Latin1,
+                }],
+                checks: vec![
+                    CheckTiming {
+                        id: "EG-001".to_string(),
+                        description: "Flags high-entropy strings".to_string(),
+                        duration_ms: 12,
+                        findings: 2,
+                    },
+                    CheckTiming {
+                        id: "FF-001".to_string(),
+                        description: "Flags forbidden files".to_string(),
+                        duration_ms: 3,
+                        findings: 0,
+                    },
+                ],
+                ..Default::default()
+            },
+        }
+    }
+
+    /// A commit message finding, which has no file to point at.
+    fn commit_report() -> Report {
+        Report {
+            findings: vec![Finding {
+                id: "CM-001".to_string(),
+                fingerprint: "fp-commit".to_string(),
+                title: "Forbidden pattern in commit message".to_string(),
+                message: "Commit message matches `internal`".to_string(),
+                severity: Severity::High,
+                location: Some(Location {
+                    line: Some(2),
+                    column: Some(5),
+                    commit: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
+                    ..Location::file("<commit message>")
+                }),
+                tags: vec!["commit".to_string(), "pattern".to_string()],
+                snippet: None,
+                details: None,
+            }],
+            duration_ms: 10,
+            coverage: Default::default(),
+        }
+    }
+
+    /// Compares against `testdata/<name>`; the tool version is written as `VERSION`.
+    fn assert_golden(name: &str, actual: &str) {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/output/testdata")
+            .join(name);
+        let expected = fs::read_to_string(&path).unwrap();
+        let actual = actual.replace(&format!("\"{}\"", env!("CARGO_PKG_VERSION")), "\"VERSION\"");
+        assert_eq!(actual, expected, "{} differs", path.display());
+    }
+
+    #[test]
+    fn test_sarif() {
+        assert_golden("report.sarif", &sarif::render(&sample_report()).unwrap());
+        assert_golden("commit.sarif", &sarif::render(&commit_report()).unwrap());
+    }
+
+    #[test]
+    fn test_junit() {
+        assert_golden("report.junit.xml", &junit::render(&sample_report()));
+    }
+
+    #[test]
+    fn test_checkstyle() {
+        assert_golden(
+            "report.checkstyle.xml",
+            &checkstyle::render(&sample_report()),
+        );
+    }
+
+    #[test]
+    fn test_gitlab() {
+        assert_golden(
+            "report.gitlab.json",
+            &gitlab::render(&sample_report()).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_xml_escape() {
+        assert_eq!(
+            xml_escape("a<b> & \"c\"\n'd'\u{1b}"),
+            "a&lt;b&gt; &amp; &quot;c&quot;&#10;&apos;d&apos;"
+        );
+    }
+}
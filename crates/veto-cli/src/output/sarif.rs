@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+
+use veto_core::model::Location;
+use veto_core::{Report, Severity};
+
+use super::report_checks;
+
+/// SARIF 2.1.0, one run with a rule per check.
+pub fn render(report: &Report) -> Result<String> {
+    let rules: Vec<Value> = report_checks(report)
+        .iter()
+        .map(|c| {
+            json!({
+                "id": c.id,
+                "shortDescription": { "text": c.description },
+            })
+        })
+        .collect();
+
+    // Byte offsets are into the decoded text, which only matches the file for plain UTF-8
+    let decoded: HashSet<&str> = report
+        .coverage
+        .files_decoded
+        .iter()
+        .map(|d| d.file.as_str())
+        .collect();
+    let results: Vec<Value> = report
+        .findings
+        .iter()
+        .map(|f| {
+            let mut result = json!({
+                "ruleId": f.id,
+                "level": level(f.severity),
+                "message": { "text": format!("{}: {}", f.title, f.message) },
+                "partialFingerprints": { "vetoFingerprint/v1": f.fingerprint },
+                "properties": { "severity": format!("{:?}", f.severity).to_lowercase(), "tags": f.tags },
+            });
+            if let Some(loc) = &f.location {
+                // A commit's message or author has no artifact to point at
+                result["locations"] = if loc.is_file() {
+                    json!([{
+                        "physicalLocation": physical_location(loc, !decoded.contains(loc.file.as_str()))
+                    }])
+                } else {
+                    json!([{
+                        "logicalLocations": [{
+                            "name": loc.file,
+                            "fullyQualifiedName": loc.subject(),
+                            "kind": "resource",
+                        }]
+                    }])
+                };
+                if let Some(commit) = &loc.commit {
+                    result["properties"]["commit"] = json!(commit);
+                }
+            }
+            result
+        })
+        .collect();
+
+    let sarif = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "veto",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    });
+    Ok(serde_json::to_string_pretty(&sarif)? + "\n")
+}
+
+/// `byte_offsets`: whether `loc`'s byte offsets are also offsets into the file.
+fn physical_location(loc: &Location, byte_offsets: bool) -> Value {
+    let mut region = Map::new();
+    let mut put = |key: &str, value: Option<u64>| {
+        if let Some(v) = value {
+            region.insert(key.to_string(), json!(v));
+        }
+    };
+    put("startLine", loc.line.map(u64::from));
+    put("startColumn", loc.column.map(u64::from));
+    put("endLine", loc.end_line.map(u64::from));
+    put("endColumn", loc.end_column.map(u64::from));
+    if byte_offsets {
+        put("byteOffset", loc.start_byte);
+        put(
+            "byteLength",
+            loc.start_byte.zip(loc.end_byte).map(|(s, e)| e - s),
+        );
+    }
+
+    let mut physical = json!({ "artifactLocation": { "uri": uri_path(&loc.file) } });
+    if !region.is_empty() {
+        physical["region"] = Value::Object(region);
+    }
+    physical
+}
+
+/// Percent-encodes a relative path for `artifactLocation.uri`.
+fn uri_path(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for b in path.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low => "note",
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use veto_core::model::Coverage;
+use veto_core::{Finding, Report, Severity};
+
+use super::{severity_name, TextOptions};
+
+const SEVERITIES: [Severity; 4] = [
+    Severity::Critical,
+    Severity::High,
+    Severity::Medium,
+    Severity::Low,
+];
+
+pub fn render(report: &Report, opts: &TextOptions) -> String {
+    let style = Style { color: opts.color };
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", summary_line(report, &style));
+    if opts.quiet {
+        return out;
+    }
+
+    // Group by file, sorted by position; findings without a location go last.
+    let mut by_file: BTreeMap<Option<String>, Vec<&Finding>> = BTreeMap::new();
+    for f in &report.findings {
+        by_file
+            .entry(f.location.as_ref().map(|l| l.subject()))
+            .or_default()
+            .push(f);
+    }
+    let mut groups: Vec<_> = by_file.into_iter().collect();
+    groups.sort_by_key(|(file, _)| file.is_none());
+
+    for (file, mut findings) in groups {
+        findings.sort_by_key(|f| {
+            let loc = f.location.as_ref();
+            (
+                loc.and_then(|l| l.line),
+                loc.and_then(|l| l.column),
+                std::cmp::Reverse(f.severity),
+            )
+        });
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "{}",
+            style.bold(file.as_deref().unwrap_or("(no file)"))
+        );
+        for f in findings {
+            render_finding(&mut out, f, opts, &style);
+        }
+    }
+
+    if !report.findings.is_empty() {
+        let _ = writeln!(out);
+        render_summary_table(&mut out, report, &style);
+    }
+    let _ = writeln!(out);
+    render_coverage(&mut out, &report.coverage, opts.explain);
+    out
+}
+
+fn summary_line(report: &Report, style: &Style) -> String {
+    if report.findings.is_empty() {
+        return format!("OK (no findings) — {}ms", report.duration_ms);
+    }
+    let counts: Vec<String> = SEVERITIES
+        .iter()
+        .map(|&sev| {
+            let n = report.findings.iter().filter(|f| f.severity == sev).count();
+            let text = format!("{} {}", n, severity_name(sev).to_lowercase());
+            if n > 0 {
+                style.severity(sev, &text)
+            } else {
+                text
+            }
+        })
+        .collect();
+    format!(
+        "Found {} issue(s): {} — {}ms",
+        report.findings.len(),
+        counts.join(", "),
+        report.duration_ms
+    )
+}
+
+fn render_finding(out: &mut String, f: &Finding, opts: &TextOptions, style: &Style) {
+    let pos = match f.location.as_ref().map(|l| (l.line, l.column)) {
+        Some((Some(line), Some(column))) => format!("{}:{}", line, column),
+        Some((Some(line), None)) => line.to_string(),
+        _ => "-".to_string(),
+    };
+    let severity = format!("{:<8}", severity_name(f.severity));
+    let _ = writeln!(
+        out,
+        "  {:>7}  {} {} [{}]",
+        pos,
+        style.severity(f.severity, &severity),
+        f.title,
+        f.id
+    );
+    let _ = writeln!(out, "           {}", f.message);
+    if let Some(snippet) = &f.snippet {
+        let _ = writeln!(out, "           {}", style.dim(&format!("> {}", snippet)));
+    }
+    if opts.explain {
+        let _ = writeln!(out, "           [Explain] fingerprint={}", f.fingerprint);
+        if let Some(d) = &f.details {
+            let _ = writeln!(
+                out,
+                "           [Explain] entropy={:.2}, normalized={:.2}, len={}, charset={}",
+                d.entropy, d.normalized_entropy, d.token_len, d.charset
+            );
+            if let Some(classifier) = &d.classifier {
+                let _ = writeln!(
+                    out,
+                    "           [Explain] demoted: matches known-benign {}",
+                    classifier
+                );
+            }
+        }
+    }
+}
+
+/// Findings per check and severity.
+fn render_summary_table(out: &mut String, report: &Report, style: &Style) {
+    let mut ids: Vec<&str> = report
+        .coverage
+        .checks
+        .iter()
+        .map(|c| c.id.as_str())
+        .collect();
+    for f in &report.findings {
+        if !ids.contains(&f.id.as_str()) {
+            ids.push(&f.id);
+        }
+    }
+
+    let width = ids.iter().map(|id| id.len()).max().unwrap_or(0).max(5);
+    let mut header = format!("{:<width$}", "Check", width = width);
+    for sev in SEVERITIES {
+        let _ = write!(header, "  {:>8}", severity_name(sev));
+    }
+    let _ = writeln!(
+        out,
+        "{}",
+        style.bold(&format!("{}  {:>8}", header, "TOTAL"))
+    );
+
+    for id in ids {
+        let findings: Vec<&Finding> = report.findings.iter().filter(|f| f.id == id).collect();
+        let mut row = format!("{:<width$}", id, width = width);
+        for sev in SEVERITIES {
+            let n = findings.iter().filter(|f| f.severity == sev).count();
+            let cell = format!("{:>8}", n);
+            let _ = write!(
+                row,
+                "  {}",
+                if n > 0 {
+                    style.severity(sev, &cell)
+                } else {
+                    cell
+                }
+            );
+        }
+        let _ = writeln!(out, "{}  {:>8}", row, findings.len());
+    }
+}
+
+fn render_coverage(out: &mut String, coverage: &Coverage, explain: bool) {
+    let _ = writeln!(
+        out,
+        "Coverage: {} file(s) scanned, {} skipped, {} truncated",
+        coverage.files_scanned,
+        coverage.files_skipped.len(),
+        coverage.files_truncated.len()
+    );
+    for s in &coverage.files_skipped {
+        let _ = writeln!(out, "  - skipped {} ({})", s.file, s.reason.as_str());
+    }
+    for t in &coverage.files_truncated {
+        let mut notes = vec![];
+        if t.lines_skipped > 0 {
+            notes.push(format!("{} long line(s) skipped", t.lines_skipped));
+        }
+        if t.token_limit_reached {
+            notes.push("token limit reached".to_string());
+        }
+        let _ = writeln!(out, "  - truncated {} ({})", t.file, notes.join(", "));
+    }
+    for d in &coverage.files_decoded {
+        let _ = writeln!(out, "  - decoded {} as {}", d.file, d.encoding.as_str());
+    }
+    if explain {
+        for d in &coverage.tokens_dropped {
+            let _ = writeln!(
+                out,
+                "  [Explain] {} dropped {} token(s) as known-benign {}",
+                d.check, d.count, d.classifier
+            );
+        }
+    }
+    if !coverage.checks.is_empty() {
+        let timings: Vec<String> = coverage
+            .checks
+            .iter()
+            .map(|c| format!("{} {}ms ({})", c.id, c.duration_ms, c.findings))
+            .collect();
+        let _ = writeln!(out, "Checks: {}", timings.join(", "));
+    }
+}
+
+/// ANSI styling, a no-op when colour is off.
+struct Style {
+    color: bool,
+}
+
+impl Style {
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn severity(&self, severity: Severity, text: &str) -> String {
+        let code = match severity {
+            Severity::Critical => "1;35",
+            Severity::High => "1;31",
+            Severity::Medium => "33",
+            Severity::Low => "36",
+        };
+        self.paint(code, text)
+    }
+
+    fn bold(&self, text: &str) -> String {
+        self.paint("1", text)
+    }
+
+    fn dim(&self, text: &str) -> String {
+        self.paint("2", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use veto_core::model::Location;
+
+    fn finding(file: &str, line: u32, severity: Severity) -> Finding {
+        Finding {
+            id: "EG-001".into(),
+            fingerprint: String::new(),
+            title: "t".into(),
+            message: "m".into(),
+            severity,
+            location: Some(Location {
+                line: Some(line),
+                ..Location::file(file)
+            }),
+            tags: vec![],
+            snippet: None,
+            details: None,
+        }
+    }
+
+    #[test]
+    fn test_grouped_sorted_and_quiet() {
+        let report = Report {
+            findings: vec![
+                finding("b.rs", 9, Severity::Low),
+                finding("a.rs", 7, Severity::High),
+                finding("b.rs", 2, Severity::Critical),
+            ],
+            duration_ms: 5,
+            coverage: Coverage::default(),
+        };
+
+        let out = render(&report, &TextOptions::default());
+        let a = out.find("\na.rs\n").unwrap();
+        let b = out.find("\nb.rs\n").unwrap();
+        let b2 = out.find("2  CRITICAL").unwrap();
+        let b9 = out.find("9  LOW").unwrap();
+        assert!(a < b && b < b2 && b2 < b9);
+        assert!(!out.contains('\x1b'));
+
+        let quiet = TextOptions {
+            quiet: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            render(&report, &quiet),
+            "Found 3 issue(s): 1 critical, 1 high, 0 medium, 1 low — 5ms\n"
+        );
+    }
+}
@@ -14,3 +14,30 @@ pub fn load_from(path: impl AsRef<Path>) -> Result<Config> {
     let cfg: Config = toml::from_str(&text).context("failed to parse config file")?;
     Ok(cfg)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_ext_migration() {
+        let defaults = Config::default().ignore_paths();
+        assert!(defaults.contains(&"**/vendor/**".to_string()));
+
+        let cfg: Config = toml::from_str(
+            r#"
+            [ignore]
+            paths = ["*.snap"]
+
+            [entropy_guard]
+            ignore_ext = ["png", "svg"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.ignore_paths(), vec!["*.snap", "*.png", "*.svg"]);
+        // Without `[ignore] paths` the extensions extend the defaults
+        let cfg: Config = toml::from_str("[entropy_guard]\nignore_ext = [\"png\"]\n").unwrap();
+        assert_eq!(cfg.ignore_paths()[..defaults.len()], defaults[..]);
+        assert_eq!(cfg.ignore_paths().last().unwrap(), "*.png");
+    }
+}
@@ -9,7 +9,32 @@ pub struct Config {
     #[serde(default)]
     pub allowlist: AllowlistConfig,
     #[serde(default)]
+    pub ignore: IgnoreConfig,
+    #[serde(default)]
     pub entropy_guard: EntropyGuardConfig,
+    #[serde(default)]
+    pub forbidden_files: ForbiddenFilesConfig,
+    #[serde(default)]
+    pub large_files: LargeFilesConfig,
+    #[serde(default)]
+    pub commits: CommitsConfig,
+    #[serde(default)]
+    pub npm: NpmConfig,
+    #[serde(default)]
+    pub cargo: CargoConfig,
+    #[serde(default)]
+    pub rules: Vec<RuleConfig>,
+}
+
+impl Config {
+    /// `[ignore] paths`, plus `*.<ext>` for each deprecated `entropy_guard.ignore_ext`.
+    pub fn ignore_paths(&self) -> Vec<String> {
+        let mut patterns = self.ignore.paths.clone();
+        if let Some(exts) = &self.entropy_guard.ignore_ext {
+            patterns.extend(exts.iter().map(|ext| format!("*.{}", ext)));
+        }
+        patterns
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +43,13 @@ pub struct OutputConfig {
     pub format: String, // "text" | "json"
     #[serde(default = "default_fail_on")]
     pub fail_on: String, // "low" | "medium" | "high" | "critical"
+    #[serde(default = "default_skips_as_findings")]
+    pub skips_as_findings: String, // "off" | "low" | "medium" | "high" | "critical"
+    /// HMAC key for finding fingerprints (empty = built-in default key)
+    #[serde(default)]
+    pub fingerprint_key: String,
+    #[serde(default)]
+    pub redaction: RedactionConfig,
 }
 
 fn default_format() -> String {
@@ -26,12 +58,40 @@ fn default_format() -> String {
 fn default_fail_on() -> String {
     "high".to_string()
 }
+fn default_skips_as_findings() -> String {
+    "off".to_string()
+}
 
 impl Default for OutputConfig {
     fn default() -> Self {
         Self {
             format: default_format(),
             fail_on: default_fail_on(),
+            skips_as_findings: default_skips_as_findings(),
+            fingerprint_key: String::new(),
+            redaction: RedactionConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionConfig {
+    #[serde(default = "default_redaction_mode")]
+    pub mode: String, // "partial" | "full" | "hash" | "length"
+    /// Salt for `mode = "hash"`
+    #[serde(default)]
+    pub salt: String,
+}
+
+fn default_redaction_mode() -> String {
+    "partial".to_string()
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            mode: default_redaction_mode(),
+            salt: String::new(),
         }
     }
 }
@@ -60,6 +120,75 @@ pub struct AllowlistConfig {
     pub patterns: Vec<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IgnoreConfig {
+    /// Path globs no check looks at. Globs without "/" match the file name at
+    /// any depth; globs with "/" match the repo-relative path.
+    #[serde(default = "default_ignore_paths")]
+    pub paths: Vec<String>,
+}
+
+fn default_ignore_paths() -> Vec<String> {
+    [
+        // Lockfiles and checksum databases: hashes, not secrets
+        "Cargo.lock",
+        "package-lock.json",
+        "npm-shrinkwrap.json",
+        "pnpm-lock.yaml",
+        "yarn.lock",
+        "bun.lock",
+        "composer.lock",
+        "Gemfile.lock",
+        "Pipfile.lock",
+        "poetry.lock",
+        "uv.lock",
+        "go.sum",
+        "go.work.sum",
+        "flake.lock",
+        "packages.lock.json",
+        "pubspec.lock",
+        "Podfile.lock",
+        "mix.lock",
+        // Generated bundles
+        "*.min.js",
+        "*.min.css",
+        "*.map",
+        // Media, fonts and archives: binary, but not always with a NUL byte
+        // early enough to be recognised as such
+        "*.png",
+        "*.jpg",
+        "*.jpeg",
+        "*.gif",
+        "*.webp",
+        "*.ico",
+        "*.pdf",
+        "*.mp3",
+        "*.mp4",
+        "*.mov",
+        "*.webm",
+        "*.woff",
+        "*.woff2",
+        "*.ttf",
+        "*.otf",
+        "*.zip",
+        "*.gz",
+        // Vendored dependencies
+        "**/node_modules/**",
+        "**/vendor/**",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+impl Default for IgnoreConfig {
+    fn default() -> Self {
+        Self {
+            paths: default_ignore_paths(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntropyGuardConfig {
     #[serde(default = "default_enabled")]
@@ -68,14 +197,33 @@ pub struct EntropyGuardConfig {
     pub min_length: usize,
     #[serde(default = "default_threshold")]
     pub threshold: f64,
+    /// Per-charset thresholds; unset charsets use `threshold` (hex: `threshold + 0.5`)
+    #[serde(default)]
+    pub thresholds: CharsetThresholdsConfig,
+    /// Judge tokens by entropy normalised to their charset and length (0..1)
+    /// against this instead of by `threshold`/`thresholds`
+    #[serde(default)]
+    pub normalized_threshold: Option<f64>,
+    /// Known-benign token shapes and what to do with them
     #[serde(default)]
-    pub ignore_ext: Vec<String>,
+    pub benign: BenignConfig,
+    /// Deprecated: extensions, added to `[ignore] paths` as `*.<ext>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignore_ext: Option<Vec<String>>,
     #[serde(default = "default_max_file_bytes")]
     pub max_file_bytes: u64,
     #[serde(default = "default_max_line_length")]
     pub max_line_length: usize,
     #[serde(default = "default_max_tokens_per_file")]
     pub max_tokens_per_file: usize,
+    #[serde(default = "default_snippet_context")]
+    pub snippet_context: usize,
+    /// Lines that must be flagged (`veto rules test`)
+    #[serde(default)]
+    pub should_match: Vec<String>,
+    /// Lines that must not be flagged (`veto rules test`)
+    #[serde(default)]
+    pub should_not_match: Vec<String>,
 }
 
 fn default_enabled() -> bool {
@@ -96,6 +244,9 @@ fn default_max_line_length() -> usize {
 fn default_max_tokens_per_file() -> usize {
     2_000
 }
+fn default_snippet_context() -> usize {
+    40
+}
 
 impl Default for EntropyGuardConfig {
     fn default() -> Self {
@@ -103,17 +254,396 @@ impl Default for EntropyGuardConfig {
             enabled: default_enabled(),
             min_length: default_min_length(),
             threshold: default_threshold(),
-            ignore_ext: vec![
-                "png".into(),
-                "jpg".into(),
-                "gif".into(),
-                "mp4".into(),
-                "pdf".into(),
-                "lock".into(),
-            ],
+            thresholds: CharsetThresholdsConfig::default(),
+            normalized_threshold: None,
+            benign: BenignConfig::default(),
+            ignore_ext: None,
             max_file_bytes: default_max_file_bytes(),
             max_line_length: default_max_line_length(),
             max_tokens_per_file: default_max_tokens_per_file(),
+            snippet_context: default_snippet_context(),
+            should_match: vec![],
+            should_not_match: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenignConfig {
+    #[serde(default = "default_benign_action")]
+    pub git_sha: String, // "drop" | "demote" | "off"
+    #[serde(default = "default_benign_action")]
+    pub uuid: String,
+    #[serde(default = "default_benign_action")]
+    pub sri: String,
+    #[serde(default = "default_benign_action")]
+    pub go_sum: String,
+    #[serde(default = "default_benign_action")]
+    pub nix_store: String,
+    #[serde(default = "default_benign_action")]
+    pub data_uri: String,
+}
+
+fn default_benign_action() -> String {
+    "drop".to_string()
+}
+
+impl Default for BenignConfig {
+    fn default() -> Self {
+        Self {
+            git_sha: default_benign_action(),
+            uuid: default_benign_action(),
+            sri: default_benign_action(),
+            go_sum: default_benign_action(),
+            nix_store: default_benign_action(),
+            data_uri: default_benign_action(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CharsetThresholdsConfig {
+    pub hex: Option<f64>,
+    pub base64: Option<f64>,
+    pub base64url: Option<f64>,
+    pub alnum: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForbiddenFilesConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_forbidden_patterns")]
+    pub patterns: Vec<String>,
+    #[serde(default = "default_forbidden_allow")]
+    pub allow: Vec<String>,
+    #[serde(default = "default_forbidden_severity")]
+    pub severity: String, // "low" | "medium" | "high" | "critical"
+    /// Paths that must be forbidden (`veto rules test`)
+    #[serde(default)]
+    pub should_match: Vec<String>,
+    /// Paths that must be allowed (`veto rules test`)
+    #[serde(default)]
+    pub should_not_match: Vec<String>,
+}
+
+fn default_forbidden_patterns() -> Vec<String> {
+    [
+        ".env",
+        ".env.*",
+        "*.pem",
+        "*.key",
+        "*.p12",
+        "*.pfx",
+        "*.jks",
+        "id_rsa",
+        "id_dsa",
+        "id_ecdsa",
+        "id_ed25519",
+        "*.tfstate",
+        "*.tfstate.backup",
+        "credentials.json",
+        "service-account*.json",
+        ".npmrc",
+        ".pypirc",
+        ".netrc",
+        ".git-credentials",
+        "kubeconfig",
+        "**/.kube/config",
+        "*.sqlite",
+        "*.sqlite3",
+        "*.db",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+fn default_forbidden_allow() -> Vec<String> {
+    vec![
+        ".env.example".into(),
+        ".env.sample".into(),
+        ".env.template".into(),
+    ]
+}
+fn default_forbidden_severity() -> String {
+    "high".to_string()
+}
+
+impl Default for ForbiddenFilesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            patterns: default_forbidden_patterns(),
+            allow: default_forbidden_allow(),
+            severity: default_forbidden_severity(),
+            should_match: vec![],
+            should_not_match: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NpmConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Directory of OSV advisories (JSON) to match locked packages against (empty = none)
+    #[serde(default)]
+    pub osv_db: String,
+    /// URL prefixes packages may be resolved to, compared without the scheme
+    #[serde(default = "default_npm_registries")]
+    pub registries: Vec<String>,
+    #[serde(default)]
+    pub severity: NpmSeverityConfig,
+}
+
+fn default_npm_registries() -> Vec<String> {
+    vec![
+        "https://registry.npmjs.org/".to_string(),
+        "https://registry.yarnpkg.com/".to_string(),
+    ]
+}
+
+impl Default for NpmConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            osv_db: String::new(),
+            registries: default_npm_registries(),
+            severity: NpmSeverityConfig::default(),
+        }
+    }
+}
+
+/// Per-rule severity: "low" | "medium" | "high" | "critical" | "off"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NpmSeverityConfig {
+    /// For advisories that carry no severity of their own
+    #[serde(default = "default_severity_high")]
+    pub advisory: String,
+    #[serde(default = "default_severity_medium")]
+    pub registry: String,
+    #[serde(default = "default_severity_high")]
+    pub insecure_url: String,
+    #[serde(default = "default_severity_medium")]
+    pub integrity: String,
+}
+
+impl Default for NpmSeverityConfig {
+    fn default() -> Self {
+        Self {
+            advisory: default_severity_high(),
+            registry: default_severity_medium(),
+            insecure_url: default_severity_high(),
+            integrity: default_severity_medium(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CargoConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Registry index URLs approved in addition to crates.io
+    #[serde(default)]
+    pub registries: Vec<String>,
+    /// Crate names that must not appear in Cargo.lock
+    #[serde(default)]
+    pub banned: Vec<String>,
+    /// Allowed SPDX license ids (empty = licenses are not checked)
+    #[serde(default)]
+    pub licenses: Vec<String>,
+    /// Crates index snapshot for yanked / license metadata (empty = none)
+    #[serde(default)]
+    pub index: String,
+    #[serde(default)]
+    pub severity: CargoSeverityConfig,
+}
+
+impl Default for CargoConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            registries: vec![],
+            banned: vec![],
+            licenses: vec![],
+            index: String::new(),
+            severity: CargoSeverityConfig::default(),
+        }
+    }
+}
+
+/// Per-rule severity: "low" | "medium" | "high" | "critical" | "off"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CargoSeverityConfig {
+    #[serde(default = "default_severity_high")]
+    pub source: String,
+    #[serde(default = "default_severity_medium")]
+    pub git_rev: String,
+    #[serde(default = "default_severity_high")]
+    pub banned: String,
+    #[serde(default = "default_severity_medium")]
+    pub yanked: String,
+    #[serde(default = "default_severity_medium")]
+    pub license: String,
+}
+
+impl Default for CargoSeverityConfig {
+    fn default() -> Self {
+        Self {
+            source: default_severity_high(),
+            git_rev: default_severity_medium(),
+            banned: default_severity_high(),
+            yanked: default_severity_medium(),
+            license: default_severity_medium(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LargeFilesConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_large_max_bytes")]
+    pub max_bytes: u64,
+    #[serde(default = "default_binary_allow")]
+    pub binary_allow: Vec<String>,
+    #[serde(default)]
+    pub severity: LargeFilesSeverityConfig,
+}
+
+fn default_large_max_bytes() -> u64 {
+    5_242_880 // 5MB
+}
+fn default_binary_allow() -> Vec<String> {
+    [
+        "*.png", "*.jpg", "*.jpeg", "*.gif", "*.ico", "*.webp", "*.pdf", "*.woff", "*.woff2",
+        "*.ttf", "*.otf",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+impl Default for LargeFilesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            max_bytes: default_large_max_bytes(),
+            binary_allow: default_binary_allow(),
+            severity: LargeFilesSeverityConfig::default(),
+        }
+    }
+}
+
+/// Per-rule severity: "low" | "medium" | "high" | "critical" | "off"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LargeFilesSeverityConfig {
+    #[serde(default = "default_severity_high")]
+    pub oversize: String,
+    #[serde(default = "default_severity_medium")]
+    pub binary: String,
+    #[serde(default = "default_severity_high")]
+    pub lfs: String,
+}
+
+fn default_severity_high() -> String {
+    "high".to_string()
+}
+fn default_severity_medium() -> String {
+    "medium".to_string()
+}
+
+impl Default for LargeFilesSeverityConfig {
+    fn default() -> Self {
+        Self {
+            oversize: default_severity_high(),
+            binary: default_severity_medium(),
+            lfs: default_severity_high(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitsConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Regexes that must not appear in commit messages (e.g. internal hostnames)
+    #[serde(default)]
+    pub deny_patterns: Vec<String>,
+    /// Allowed author/committer email domains, subdomains included (empty = any)
+    #[serde(default)]
+    pub email_domains: Vec<String>,
+    /// Regex every non-merge commit message must match (empty = off)
+    #[serde(default)]
+    pub message_pattern: String,
+    #[serde(default)]
+    pub severity: CommitsSeverityConfig,
+}
+
+impl Default for CommitsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            deny_patterns: vec![],
+            email_domains: vec![],
+            message_pattern: String::new(),
+            severity: CommitsSeverityConfig::default(),
+        }
+    }
+}
+
+/// Per-rule severity: "low" | "medium" | "high" | "critical" | "off"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitsSeverityConfig {
+    /// Secrets (entropy) and `deny_patterns` in messages
+    #[serde(default = "default_severity_high")]
+    pub secret: String,
+    #[serde(default = "default_severity_medium")]
+    pub email: String,
+    #[serde(default = "default_severity_low")]
+    pub format: String,
+}
+
+fn default_severity_low() -> String {
+    "low".to_string()
+}
+
+impl Default for CommitsSeverityConfig {
+    fn default() -> Self {
+        Self {
+            secret: default_severity_high(),
+            email: default_severity_medium(),
+            format: default_severity_low(),
         }
     }
 }
+
+/// A custom regex rule (`[[rules]]`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleConfig {
+    pub id: String,
+    pub title: String,
+    /// Matched line by line; a capture group named `secret` narrows what is
+    /// entropy-gated, redacted and located
+    pub regex: String,
+    #[serde(default = "default_severity_medium")]
+    pub severity: String, // "low" | "medium" | "high" | "critical" | "off"
+    /// Path globs the rule applies to (empty = all files)
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Path globs the rule never applies to, even if they match `paths`
+    #[serde(default)]
+    pub exclude_paths: Vec<String>,
+    /// Minimum Shannon entropy of the match (or `secret` group)
+    #[serde(default)]
+    pub min_entropy: Option<f64>,
+    /// Regexes that suppress a match when they match its text
+    #[serde(default)]
+    pub allowlist: Vec<String>,
+    /// Lines the rule must match (`veto rules test`)
+    #[serde(default)]
+    pub should_match: Vec<String>,
+    /// Lines the rule must not match (`veto rules test`)
+    #[serde(default)]
+    pub should_not_match: Vec<String>,
+}
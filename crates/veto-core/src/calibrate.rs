@@ -0,0 +1,228 @@
+use anyhow::Result;
+
+use crate::checks::entropy_guard::{
+    lines_with_offsets, shannon_entropy, CharsetHint, EntropyGuard, Thresholds,
+};
+use crate::targets::get_target_files;
+use crate::Context;
+
+/// Per-charset thresholds compared by `veto calibrate`.
+pub const CANDIDATE_THRESHOLDS: [f64; 9] = [3.5, 3.75, 4.0, 4.25, 4.5, 4.75, 5.0, 5.25, 5.5];
+
+/// Tokens shorter than this are not sampled, whatever `min_length` is.
+pub const MIN_LENGTH_FLOOR: usize = 16;
+
+/// A candidate token reduced to what calibration needs; the text is never kept.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenSample {
+    pub charset: CharsetHint,
+    pub entropy: f64,
+    pub len: usize,
+}
+
+impl TokenSample {
+    /// Whether `EntropyGuard` flags the token when its charset's threshold is `threshold`.
+    pub fn flagged_at(&self, threshold: f64) -> bool {
+        self.entropy > threshold
+    }
+}
+
+/// Entropy distribution of one charset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharsetStats {
+    pub charset: CharsetHint,
+    pub count: usize,
+    pub min: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+/// Every candidate token in the files of `ctx`, which are assumed to hold no
+/// secrets. Files `ctx.ignore` matches are skipped, as in a scan.
+pub fn token_samples(ctx: &Context, guard: &EntropyGuard) -> Result<Vec<TokenSample>> {
+    let guard = sampling_guard(guard);
+    let mut samples = vec![];
+    for file in get_target_files(ctx, guard.max_file_bytes)?.iter() {
+        for (_, line) in lines_with_offsets(&file.text) {
+            if line.len() > guard.max_line_length {
+                continue;
+            }
+            samples.extend(
+                guard
+                    .candidates(line)
+                    .into_iter()
+                    .map(|(token, charset)| sample(token, charset)),
+            );
+        }
+    }
+    Ok(samples)
+}
+
+/// One sample per non-empty line of the files of `ctx`, each line holding a
+/// known secret: the candidate token closest to being flagged at `guard`'s
+/// thresholds, or `None` if no token of the line is a candidate (too short,
+/// allowlisted, too uniform).
+pub fn secret_samples(ctx: &Context, guard: &EntropyGuard) -> Result<Vec<Option<TokenSample>>> {
+    let thresholds = guard.thresholds;
+    let margin = |s: &TokenSample| s.entropy - thresholds.get(s.charset);
+    let guard = sampling_guard(guard);
+    let mut samples = vec![];
+    for file in get_target_files(ctx, guard.max_file_bytes)?.iter() {
+        for (_, line) in lines_with_offsets(&file.text) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let best = guard
+                .candidates(line)
+                .into_iter()
+                .map(|(token, charset)| sample(token, charset))
+                .max_by(|a, b| margin(a).total_cmp(&margin(b)));
+            samples.push(best);
+        }
+    }
+    Ok(samples)
+}
+
+/// `min_length` to suggest: short enough to keep every known secret a
+/// candidate (but not below `MIN_LENGTH_FLOOR`), otherwise `configured`.
+pub fn suggest_min_length(secrets: &[Option<TokenSample>], configured: usize) -> usize {
+    secrets
+        .iter()
+        .flatten()
+        .map(|s| s.len)
+        .min()
+        .map_or(configured, |shortest| {
+            configured.min(shortest).max(MIN_LENGTH_FLOOR)
+        })
+}
+
+/// Thresholds to suggest, each among `CANDIDATE_THRESHOLDS`.
+///
+/// Per charset: with known secrets of that charset, the highest candidate that
+/// still flags all of them (the fewest false positives at full recall).
+/// Without: the lowest candidate with no false positives in `tokens`. Charsets
+/// with no samples at all keep their `current` threshold.
+pub fn suggest_thresholds(
+    tokens: &[TokenSample],
+    secrets: &[Option<TokenSample>],
+    current: Thresholds,
+) -> Thresholds {
+    let mut suggested = current;
+    for charset in CharsetHint::ALL {
+        let secrets: Vec<_> = secrets
+            .iter()
+            .flatten()
+            .filter(|s| s.charset == charset)
+            .collect();
+        let tokens: Vec<_> = tokens.iter().filter(|s| s.charset == charset).collect();
+        let threshold = if !secrets.is_empty() {
+            CANDIDATE_THRESHOLDS
+                .into_iter()
+                .rev()
+                .find(|&t| secrets.iter().all(|s| s.flagged_at(t)))
+                .unwrap_or(CANDIDATE_THRESHOLDS[0])
+        } else if !tokens.is_empty() {
+            CANDIDATE_THRESHOLDS
+                .into_iter()
+                .find(|&t| !tokens.iter().any(|s| s.flagged_at(t)))
+                .unwrap_or(CANDIDATE_THRESHOLDS[CANDIDATE_THRESHOLDS.len() - 1])
+        } else {
+            continue;
+        };
+        suggested.set(charset, threshold);
+    }
+    suggested
+}
+
+/// Entropy distribution of `samples` per charset, in `CharsetHint` order.
+pub fn distribution(samples: &[TokenSample]) -> Vec<CharsetStats> {
+    let mut charsets: Vec<_> = samples.iter().map(|s| s.charset).collect();
+    charsets.sort();
+    charsets.dedup();
+    charsets
+        .into_iter()
+        .map(|charset| {
+            let mut values: Vec<f64> = samples
+                .iter()
+                .filter(|s| s.charset == charset)
+                .map(|s| s.entropy)
+                .collect();
+            values.sort_by(f64::total_cmp);
+            let percentile = |p: usize| values[(values.len() * p).div_ceil(100).max(1) - 1];
+            CharsetStats {
+                charset,
+                count: values.len(),
+                min: values[0],
+                p50: percentile(50),
+                p90: percentile(90),
+                p99: percentile(99),
+                max: values[values.len() - 1],
+            }
+        })
+        .collect()
+}
+
+fn sampling_guard(guard: &EntropyGuard) -> EntropyGuard {
+    EntropyGuard {
+        min_length: guard.min_length.min(MIN_LENGTH_FLOOR),
+        allowlist: guard.allowlist.clone(),
+        classifiers: guard.classifiers.clone(),
+        examples: Default::default(),
+        ..*guard
+    }
+}
+
+fn sample(token: &str, charset: CharsetHint) -> TokenSample {
+    TokenSample {
+        charset,
+        entropy: shannon_entropy(token),
+        len: token.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(charset: CharsetHint, entropy: f64) -> TokenSample {
+        TokenSample {
+            charset,
+            entropy,
+            len: 32,
+        }
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let tokens = vec![
+            s(CharsetHint::Alnum, 3.9),
+            s(CharsetHint::Alnum, 4.1),
+            s(CharsetHint::Hexish, 3.6),
+        ];
+        let secrets = vec![Some(s(CharsetHint::Base64ish, 4.8)), None];
+        let suggested = suggest_thresholds(&tokens, &secrets, Thresholds::from_base(4.2));
+        assert_eq!(
+            suggested,
+            Thresholds {
+                hex: 3.75,
+                base64: 4.75,
+                base64url: 4.2,
+                alnum: 4.25,
+            }
+        );
+
+        let short = Some(TokenSample {
+            len: 20,
+            ..s(CharsetHint::Alnum, 4.5)
+        });
+        assert_eq!(suggest_min_length(&[short, None], 24), 20);
+        assert_eq!(suggest_min_length(&[], 24), 24);
+
+        let stats = distribution(&tokens);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].charset, CharsetHint::Hexish);
+        assert_eq!((stats[1].count, stats[1].p50, stats[1].max), (2, 3.9, 4.1));
+    }
+}
@@ -0,0 +1,248 @@
+//! Known-benign token shapes: strings that look random (and so pass the
+//! entropy threshold) but are hashes or identifiers, not secrets.
+
+use std::sync::Arc;
+
+/// Recognises one known-benign shape. Plugged into `EntropyGuard::classifiers`.
+pub trait Classifier: Send + Sync {
+    /// Key in `[entropy_guard.benign]`, also shown in `--explain`.
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
+    /// Whether `token`, found at byte `start` of `line`, has this shape.
+    fn matches(&self, line: &str, start: usize, token: &str) -> bool;
+}
+
+/// What `EntropyGuard` does with a token a classifier recognises.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenignAction {
+    /// Not reported (counted in coverage).
+    Drop,
+    /// Reported at `Severity::Low`, tagged `benign`.
+    Demote,
+}
+
+/// The built-in classifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Builtin {
+    GitSha,
+    Uuid,
+    Sri,
+    GoSum,
+    NixStore,
+    DataUri,
+}
+
+impl Builtin {
+    pub const ALL: [Builtin; 6] = [
+        Builtin::GitSha,
+        Builtin::Uuid,
+        Builtin::Sri,
+        Builtin::GoSum,
+        Builtin::NixStore,
+        Builtin::DataUri,
+    ];
+
+    pub fn shared(self) -> Arc<dyn Classifier> {
+        Arc::new(self)
+    }
+}
+
+impl Classifier for Builtin {
+    fn name(&self) -> &str {
+        match self {
+            Builtin::GitSha => "git_sha",
+            Builtin::Uuid => "uuid",
+            Builtin::Sri => "sri",
+            Builtin::GoSum => "go_sum",
+            Builtin::NixStore => "nix_store",
+            Builtin::DataUri => "data_uri",
+        }
+    }
+
+    fn description(&self) -> &str {
+        match self {
+            Builtin::GitSha => "git object id (40 or 64 lowercase hex) in a git context",
+            Builtin::Uuid => "UUID (8-4-4-4-12 hex)",
+            Builtin::Sri => "subresource integrity hash (sha256-/sha384-/sha512-)",
+            Builtin::GoSum => "go.sum module hash (h1:)",
+            Builtin::NixStore => "Nix store path hash (/nix/store/<hash>-)",
+            Builtin::DataUri => "base64 image data URI (data:image/...;base64,)",
+        }
+    }
+
+    fn matches(&self, line: &str, start: usize, token: &str) -> bool {
+        match self {
+            Builtin::GitSha => {
+                matches!(token.len(), 40 | 64)
+                    && token
+                        .bytes()
+                        .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+                    && is_git_context(&line[..start])
+            }
+            Builtin::Uuid => {
+                let groups: Vec<&str> = token.split('-').collect();
+                groups.len() == 5
+                    && groups
+                        .iter()
+                        .zip([8, 4, 4, 4, 12])
+                        .all(|(g, len)| g.len() == len && is_hex(g))
+            }
+            Builtin::Sri => ["sha256-", "sha384-", "sha512-"]
+                .into_iter()
+                .zip([44, 64, 88])
+                .any(|(prefix, len)| {
+                    token
+                        .strip_prefix(prefix)
+                        .is_some_and(|digest| digest.len() == len && is_base64(digest))
+                }),
+            Builtin::GoSum => {
+                line[..start].ends_with("h1:") && token.len() == 44 && is_base64(token)
+            }
+            Builtin::NixStore => token.match_indices("nix/store/").any(|(i, m)| {
+                let hash = &token[i + m.len()..];
+                hash.len() >= 32 && hash.bytes().take(32).all(is_nix_base32)
+            }),
+            Builtin::DataUri => {
+                let before = &line[..start];
+                before.ends_with(";base64,")
+                    && before
+                        .rfind("data:")
+                        .is_some_and(|i| before[i + "data:".len()..].starts_with("image/"))
+            }
+        }
+    }
+}
+
+/// Whether text right before a hex token marks it as a git object id, not
+/// just any hex string (a hex API key looks exactly the same):
+/// `commit <sha>` (git output), `action@<sha>`, `repo.git#<sha>`,
+/// `.../commit/<sha>` URLs, or a value keyed `rev`, `commit`, `sha`, ...
+fn is_git_context(before: &str) -> bool {
+    const OUTPUT_WORDS: &[&str] = &["commit", "tree", "parent", "object"];
+    const URL_SEGMENTS: &[&str] = &["/commit/", "/commits/", "/tree/", "/blob/"];
+    const KEYS: &[&str] = &["rev", "revision", "commit", "sha", "ref"];
+
+    if before.ends_with(['@', '#']) || URL_SEGMENTS.iter().any(|s| before.ends_with(s)) {
+        return true;
+    }
+    if before.ends_with(' ') {
+        let word = before.trim_end().rsplit(' ').next().unwrap_or("");
+        if OUTPUT_WORDS.contains(&word) {
+            return true;
+        }
+    }
+    // `rev = "<sha>"`, `"rev": "<sha>"`, `GITHUB_SHA=<sha>`, `commit_sha: <sha>`
+    let Some(key) = before
+        .trim_end_matches(['"', '\'', ' '])
+        .strip_suffix(['=', ':'])
+    else {
+        return false;
+    };
+    let key = key.trim_end_matches(['"', '\'', ' ']);
+    let key = &key[key
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+        .map_or(0, |i| i + 1)..];
+    let last = key.rsplit(['_', '-']).next().unwrap_or("");
+    KEYS.contains(&last.to_ascii_lowercase().as_str())
+}
+
+fn is_hex(s: &str) -> bool {
+    s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn is_base64(s: &str) -> bool {
+    s.bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'/' || b == b'=')
+}
+
+/// Nix's base32 alphabet: digits and lowercase letters except e, o, u, t.
+fn is_nix_base32(b: u8) -> bool {
+    b.is_ascii_digit() || (b.is_ascii_lowercase() && !matches!(b, b'e' | b'o' | b'u' | b't'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The built-in classifier that recognises `token` within `line`.
+    fn classify(line: &str, token: &str) -> Option<Builtin> {
+        let start = line.find(token).unwrap();
+        Builtin::ALL
+            .into_iter()
+            .find(|c| c.matches(line, start, token))
+    }
+
+    #[test]
+    fn test_builtin_classifiers() {
+        let sha = "3f786850e387550fdab836ed7e6dc881de23001b";
+        assert_eq!(
+            classify(&format!("commit {}", sha), sha),
+            Some(Builtin::GitSha)
+        );
+        let uuid = "123e4567-e89b-12d3-a456-426614174000";
+        assert_eq!(
+            classify(&format!("id: {}", uuid), uuid),
+            Some(Builtin::Uuid)
+        );
+
+        let digest = &"Zk9xQ2bR7vLp3TnW8yHs4JdK6mXa1cEf".repeat(3)[..86];
+        let sri = format!("sha512-{}==", digest);
+        let line = format!("\"integrity\": \"{}\",", sri);
+        assert_eq!(classify(&line, &sri), Some(Builtin::Sri));
+
+        let h1 = "Zk9xQ2bR7vLp3TnW8yHs4JdK6mXa1cEf+/q8Zr2VxN7=";
+        let line = format!("golang.org/x/text v0.3.7 h1:{}", h1);
+        assert_eq!(classify(&line, h1), Some(Builtin::GoSum));
+
+        let nix = "/nix/store/0c4jr8y3s8xdsawz7ri2gm4xqwwvk3xc-hello-2";
+        assert_eq!(
+            classify(&format!("{}.10/bin", nix), nix),
+            Some(Builtin::NixStore)
+        );
+
+        let data = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk";
+        let line = format!("src=\"data:image/png;base64,{}\"", data);
+        assert_eq!(classify(&line, data), Some(Builtin::DataUri));
+
+        // Same shapes without their context are not benign
+        assert_eq!(classify(&format!("key={}", h1), h1), None);
+        assert_eq!(
+            classify(&format!("data:text/plain;base64,{}", data), data),
+            None
+        );
+        let upper = sha.to_ascii_uppercase();
+        assert_eq!(classify(&upper, &upper), None);
+    }
+
+    #[test]
+    fn test_git_sha_needs_git_context() {
+        let sha = "3f786850e387550fdab836ed7e6dc881de23001b";
+        let sha256 = "4e1f0c9a2b7d3e5f6a8c0b1d2e3f4a5b6c7d8e9f0a1b2c3d4e5f6a7b8c9d7e1f";
+        for line in [
+            format!("commit {}", sha),
+            format!("parent {}", sha256),
+            format!("uses: actions/checkout@{}", sha),
+            format!("\"resolved\": \"git+ssh://git@github.com/o/r.git#{}\"", sha),
+            format!("see https://github.com/o/r/commit/{}", sha),
+            format!("rev = \"{}\"", sha),
+            format!("\"rev\": \"{}\",", sha),
+            format!("GITHUB_SHA={}", sha),
+            format!("base_commit: '{}'", sha256),
+        ] {
+            let token = if line.contains(sha) { sha } else { sha256 };
+            assert_eq!(classify(&line, token), Some(Builtin::GitSha), "{}", line);
+        }
+
+        // A hex secret has the same shape; without git context it stays a finding
+        for line in [
+            format!("key = \"{}\"", sha),
+            format!("api_key = \"{}\"", sha),
+            format!("secret: {}", sha256),
+            format!("token={}", sha),
+            sha.to_string(),
+        ] {
+            let token = if line.contains(sha) { sha } else { sha256 };
+            assert_eq!(classify(&line, token), None, "{}", line);
+        }
+    }
+}
@@ -0,0 +1,660 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::model::{Location, SkipReason};
+use crate::targets::{get_named_files, TextFile};
+use crate::{Check, Finding, Severity};
+
+/// Index URLs of crates.io, which is always an approved source.
+pub const CRATES_IO: &[&str] = &[
+    "https://github.com/rust-lang/crates.io-index",
+    "https://index.crates.io",
+];
+
+const MAX_MANIFEST_BYTES: u64 = 16 * 1024 * 1024;
+
+// Tables of a manifest that declare dependencies (also under `[target.<cfg>]`)
+const DEPENDENCY_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Enforces a Cargo dependency policy on `Cargo.lock` and `Cargo.toml`:
+/// approved sources, pinned git dependencies, banned crates, and (with a
+/// crates index snapshot) yanked versions and allowed licenses.
+///
+/// Each rule has its own severity; `None` disables the rule.
+pub struct CargoPolicy {
+    pub enabled: bool,
+    /// Registry index URLs approved in addition to `CRATES_IO`.
+    pub registries: Vec<String>,
+    /// Crate names that must not be depended on, directly or not.
+    pub banned: Vec<String>,
+    /// Allowed SPDX license ids; empty means licenses are not checked.
+    pub licenses: Vec<String>,
+    /// Yanked and license metadata of crates.io crates.
+    pub index: Option<CratesIndex>,
+    pub source: Option<Severity>,
+    pub git_rev: Option<Severity>,
+    pub banned_severity: Option<Severity>,
+    pub yanked: Option<Severity>,
+    pub license: Option<Severity>,
+}
+
+/// A `[[package]]` of `Cargo.lock`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LockedCrate {
+    pub name: String,
+    pub version: String,
+    /// `registry+<url>`, `sparse+<url>` or `git+<url>`; `None` for path crates.
+    pub source: Option<String>,
+    /// 1-based line of the `[[package]]` header.
+    pub line: usize,
+    /// 1-based last line of the entry; a change anywhere in `line..=end_line`
+    /// (e.g. a bumped version) puts the crate in a diff's scope.
+    pub end_line: usize,
+}
+
+/// A local snapshot of a crates index, in the layout of the crates.io index
+/// (`se/rd/serde`, one JSON line per version). Lines may carry a `license`
+/// (SPDX expression), which the official index does not record.
+pub struct CratesIndex {
+    pub root: PathBuf,
+}
+
+/// A version line of a `CratesIndex` file.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct IndexVersion {
+    pub vers: String,
+    #[serde(default)]
+    pub yanked: bool,
+    #[serde(default)]
+    pub license: Option<String>,
+}
+
+impl CratesIndex {
+    /// Metadata of `version` of crate `name`, if the snapshot has it.
+    pub fn version(&self, name: &str, version: &str) -> Option<IndexVersion> {
+        let text = fs::read_to_string(self.root.join(index_path(name)?)).ok()?;
+        text.lines()
+            .filter_map(|line| serde_json::from_str::<IndexVersion>(line).ok())
+            .find(|v| v.vers == version)
+    }
+}
+
+/// Path of a crate's file in the crates.io index layout; `None` unless `name`
+/// is a valid crate name (ASCII alphanumerics, `-` and `_`), which a lockfile
+/// from an untrusted push need not contain.
+fn index_path(name: &str) -> Option<String> {
+    let valid = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    if name.is_empty() || !name.chars().all(valid) {
+        return None;
+    }
+    let name = name.to_ascii_lowercase();
+    Some(match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    })
+}
+
+impl Check for CargoPolicy {
+    fn id(&self) -> &str {
+        "CARGO-001"
+    }
+
+    fn description(&self) -> &str {
+        "Enforces the Cargo dependency policy: sources, git pins, banned crates, yanked versions and licenses"
+    }
+
+    fn run(&self, ctx: &crate::Context) -> Result<Vec<Finding>> {
+        if !self.enabled {
+            return Ok(vec![]);
+        }
+
+        let mut findings = vec![];
+        for file in get_named_files(ctx, &["Cargo.lock", "Cargo.toml"], MAX_MANIFEST_BYTES)? {
+            if file.path.ends_with("Cargo.lock") {
+                findings.extend(self.check_lock(ctx, &file));
+            } else {
+                match self.check_manifest(ctx, &file) {
+                    Some(found) => findings.extend(found),
+                    None => ctx.coverage.skipped(&file.path, SkipReason::Unreadable),
+                }
+            }
+        }
+        Ok(findings)
+    }
+}
+
+impl CargoPolicy {
+    /// Findings for the crates of a `Cargo.lock` whose entries are in scope.
+    fn check_lock(&self, ctx: &crate::Context, file: &TextFile) -> Vec<Finding> {
+        parse_cargo_lock(&file.text)
+            .iter()
+            .filter(|c| file.includes_lines(c.line, c.end_line))
+            .flat_map(|c| self.check_crate(ctx, &file.path, c))
+            .collect()
+    }
+
+    fn check_crate(&self, ctx: &crate::Context, path: &str, krate: &LockedCrate) -> Vec<Finding> {
+        let spec = format!("{}@{}", krate.name, krate.version);
+        let mut findings = vec![];
+
+        if let Some(severity) = self.banned_severity {
+            if self.banned.contains(&krate.name) {
+                findings.push(self.finding(
+                    ctx,
+                    path,
+                    krate.line,
+                    severity,
+                    "Banned crate",
+                    format!("{} is banned by the dependency policy", spec),
+                    &spec,
+                    "banned",
+                ));
+            }
+        }
+
+        let registry = krate.source.as_deref().and_then(registry_url);
+        if let (Some(severity), Some(url)) = (self.source, registry) {
+            if !self.approved(url) {
+                findings.push(self.finding(
+                    ctx,
+                    path,
+                    krate.line,
+                    severity,
+                    "Crate from an unapproved registry",
+                    format!(
+                        "{} comes from {}, which is not an approved registry",
+                        spec, url
+                    ),
+                    &spec,
+                    "source",
+                ));
+            }
+        }
+
+        // The snapshot describes crates.io; other registries may reuse names
+        let metadata = match (&self.index, registry) {
+            (Some(index), Some(url)) if is_crates_io(url) => {
+                index.version(&krate.name, &krate.version)
+            }
+            _ => None,
+        };
+        if let Some(metadata) = metadata {
+            if let (Some(severity), true) = (self.yanked, metadata.yanked) {
+                findings.push(self.finding(
+                    ctx,
+                    path,
+                    krate.line,
+                    severity,
+                    "Yanked crate version",
+                    format!("{} has been yanked from crates.io", spec),
+                    &spec,
+                    "yanked",
+                ));
+            }
+            if let (Some(severity), Some(license), false) =
+                (self.license, &metadata.license, self.licenses.is_empty())
+            {
+                if !license_allowed(license, &self.licenses) {
+                    findings.push(self.finding(
+                        ctx,
+                        path,
+                        krate.line,
+                        severity,
+                        "License not allowed",
+                        format!(
+                            "{} is licensed under `{}`, which is not in the allowed licenses",
+                            spec, license
+                        ),
+                        &spec,
+                        "license",
+                    ));
+                }
+            }
+        }
+
+        findings
+    }
+
+    /// Git dependencies of a manifest that are not pinned to a `rev`; `None`
+    /// if it is not valid TOML.
+    fn check_manifest(&self, ctx: &crate::Context, file: &TextFile) -> Option<Vec<Finding>> {
+        let manifest: toml::Table = file.text.parse().ok()?;
+        let Some(severity) = self.git_rev else {
+            return Some(vec![]);
+        };
+
+        let mut tables = vec![];
+        let mut collect = |parent: &toml::Table| {
+            for name in DEPENDENCY_TABLES {
+                if let Some(deps) = parent.get(*name).and_then(|t| t.as_table()) {
+                    tables.push(deps.clone());
+                }
+            }
+        };
+        collect(&manifest);
+        if let Some(targets) = manifest.get("target").and_then(|t| t.as_table()) {
+            for target in targets.values().filter_map(|t| t.as_table()) {
+                collect(target);
+            }
+        }
+        let workspace_deps = manifest
+            .get("workspace")
+            .and_then(|w| w.get("dependencies"))
+            .and_then(|d| d.as_table());
+        tables.extend(workspace_deps.cloned());
+
+        let mut findings = vec![];
+        for deps in &tables {
+            for (name, dep) in deps {
+                let Some(git) = dep.get("git").and_then(|g| g.as_str()) else {
+                    continue;
+                };
+                if dep.get("rev").is_some() {
+                    continue;
+                }
+                let reference = match (dep.get("branch"), dep.get("tag")) {
+                    (Some(branch), _) => format!("branch {}", branch),
+                    (None, Some(tag)) => format!("tag {}", tag),
+                    _ => "the default branch".to_string(),
+                };
+                let line = declaration_line(&file.text, name);
+                if !file.includes_line(line) {
+                    continue;
+                }
+                findings.push(self.finding(
+                    ctx,
+                    &file.path,
+                    line,
+                    severity,
+                    "Unpinned git dependency",
+                    format!(
+                        "`{}` follows {} of {}; pin it with `rev`",
+                        name, reference, git
+                    ),
+                    name,
+                    "git-rev",
+                ));
+            }
+        }
+        Some(findings)
+    }
+
+    fn approved(&self, url: &str) -> bool {
+        is_crates_io(url)
+            || self
+                .registries
+                .iter()
+                .any(|r| normalize_registry(r) == normalize_registry(url))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn finding(
+        &self,
+        ctx: &crate::Context,
+        path: &str,
+        line: usize,
+        severity: Severity,
+        title: &str,
+        message: String,
+        subject: &str,
+        tag: &str,
+    ) -> Finding {
+        Finding {
+            id: self.id().to_string(),
+            fingerprint: ctx.fingerprint(self.id(), path, &format!("{} {}", subject, tag)),
+            title: title.to_string(),
+            message,
+            severity,
+            location: Some(Location {
+                line: Some(line as u32),
+                ..Location::file(path)
+            }),
+            tags: vec!["dependency".to_string(), tag.to_string()],
+            snippet: None,
+            details: None,
+        }
+    }
+}
+
+/// Index URL of a registry source (`registry+<url>` / `sparse+<url>`); `None` for git sources.
+fn registry_url(source: &str) -> Option<&str> {
+    source
+        .strip_prefix("registry+")
+        .or_else(|| source.strip_prefix("sparse+"))
+}
+
+fn is_crates_io(url: &str) -> bool {
+    CRATES_IO.contains(&normalize_registry(url))
+}
+
+fn normalize_registry(url: &str) -> &str {
+    registry_url(url).unwrap_or(url).trim_end_matches('/')
+}
+
+/// Line declaring dependency `name` in a manifest: `name = ...`, a dotted key
+/// (`name.git = ...`) or `[...dependencies.name]`.
+fn declaration_line(text: &str, name: &str) -> usize {
+    let mut in_dependencies = false;
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            if line.ends_with(&format!("dependencies.{}]", name)) {
+                return i + 1;
+            }
+            in_dependencies = line.ends_with("dependencies]");
+        } else if in_dependencies
+            && line
+                .strip_prefix(name)
+                .is_some_and(|rest| rest.trim_start().starts_with(['=', '.']))
+        {
+            return i + 1;
+        }
+    }
+    1
+}
+
+/// `[[package]]` entries of a `Cargo.lock`, which cargo always writes in the
+/// same simple layout.
+pub fn parse_cargo_lock(text: &str) -> Vec<LockedCrate> {
+    let mut crates = vec![];
+    let mut current: Option<LockedCrate> = None;
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            crates.extend(current.take());
+            if line == "[[package]]" {
+                current = Some(LockedCrate {
+                    line: i + 1,
+                    end_line: i + 1,
+                    ..Default::default()
+                });
+            }
+            continue;
+        }
+        if let (Some(krate), false) = (current.as_mut(), line.is_empty()) {
+            krate.end_line = i + 1;
+        }
+        let (Some(krate), Some((key, value))) = (current.as_mut(), line.split_once(" = ")) else {
+            continue;
+        };
+        let value = value.trim_matches('"').to_string();
+        match key {
+            "name" => krate.name = value,
+            "version" => krate.version = value,
+            "source" => krate.source = Some(value),
+            _ => {}
+        }
+    }
+    crates.extend(current);
+    crates
+}
+
+/// Whether an SPDX license expression is satisfied by `allowed`: one side of
+/// each `OR` (or legacy `/`) and both sides of each `AND` must be allowed. A
+/// `WITH` exception is allowed if the whole term or its license is.
+pub fn license_allowed(expression: &str, allowed: &[String]) -> bool {
+    let expression = expression.trim();
+    if let Some(inner) = expression
+        .strip_prefix('(')
+        .and_then(|e| e.strip_suffix(')'))
+        .filter(|inner| balanced(inner))
+    {
+        return license_allowed(inner, allowed);
+    }
+    let alternatives = split_top_level(expression, " OR ");
+    if alternatives.len() > 1 {
+        return alternatives.iter().any(|a| license_allowed(a, allowed));
+    }
+    let alternatives = split_top_level(expression, "/");
+    if alternatives.len() > 1 {
+        return alternatives.iter().any(|a| license_allowed(a, allowed));
+    }
+    let all = split_top_level(expression, " AND ");
+    if all.len() > 1 {
+        return all.iter().all(|a| license_allowed(a, allowed));
+    }
+    let is_allowed = |id: &str| allowed.iter().any(|a| a == id);
+    is_allowed(expression)
+        || expression
+            .split_once(" WITH ")
+            .is_some_and(|(license, _)| is_allowed(license.trim()))
+}
+
+/// Splits `s` at `separator`s outside parentheses.
+fn split_top_level<'a>(s: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = vec![];
+    let mut depth = 0i32;
+    let mut start = 0;
+    let mut i = 0;
+    while i < s.len() {
+        match s.as_bytes()[i] {
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            _ if depth == 0 && s[i..].starts_with(separator) => {
+                parts.push(&s[start..i]);
+                i += separator.len();
+                start = i;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Whether the parentheses of `s` are balanced.
+fn balanced(s: &str) -> bool {
+    let mut depth = 0i32;
+    for b in s.bytes() {
+        match b {
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            return false;
+        }
+    }
+    depth == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::ScopeMode;
+    use crate::Context;
+
+    fn policy() -> CargoPolicy {
+        CargoPolicy {
+            enabled: true,
+            registries: vec!["sparse+https://cargo.corp.example/index/".into()],
+            banned: vec!["openssl".into()],
+            licenses: vec!["MIT".into(), "Apache-2.0".into()],
+            index: None,
+            source: Some(Severity::High),
+            git_rev: Some(Severity::Medium),
+            banned_severity: Some(Severity::High),
+            yanked: Some(Severity::Medium),
+            license: Some(Severity::Medium),
+        }
+    }
+
+    #[test]
+    fn test_cargo_lock() {
+        let lock = r#"# This file is automatically @generated by Cargo.
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "openssl",
+]
+
+[[package]]
+name = "openssl"
+version = "0.10.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "abc"
+
+[[package]]
+name = "internal"
+version = "1.0.0"
+source = "sparse+https://cargo.corp.example/index/"
+
+[[package]]
+name = "shady"
+version = "0.1.0"
+source = "registry+https://shady.example/index"
+"#;
+        let crates = parse_cargo_lock(lock);
+        assert_eq!(crates.len(), 4);
+        assert_eq!(
+            (crates[1].name.as_str(), crates[1].line, crates[1].end_line),
+            ("openssl", 11, 15)
+        );
+        assert_eq!(crates[0].source, None);
+
+        let ctx = Context::new(Default::default(), ScopeMode::Staged);
+        let check = policy();
+        let tags: Vec<String> = crates
+            .iter()
+            .flat_map(|c| check.check_crate(&ctx, "Cargo.lock", c))
+            .map(|f| format!("{} {}", f.location.unwrap().line.unwrap(), f.tags[1]))
+            .collect();
+        assert_eq!(tags, vec!["11 banned", "22 source"]);
+
+        // In a diff, a crate is in scope if any line of its entry changed
+        let scoped = |lines| -> Vec<String> {
+            let file = TextFile {
+                path: "Cargo.lock".into(),
+                text: lock.into(),
+                lines: Some(lines),
+            };
+            check
+                .check_lock(&ctx, &file)
+                .into_iter()
+                .map(|f| f.tags[1].clone())
+                .collect()
+        };
+        assert_eq!(scoped(vec![(13, 13)]), vec!["banned"]);
+        assert_eq!(scoped(vec![(24, 24)]), vec!["source"]);
+        assert!(scoped(vec![(1, 10), (16, 21)]).is_empty());
+    }
+
+    #[test]
+    fn test_index_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        fs::create_dir_all(root.join("ti/me")).unwrap();
+        fs::write(
+            root.join("ti/me/time"),
+            "{\"name\":\"time\",\"vers\":\"0.1.0\",\"yanked\":true,\"license\":\"MIT/Apache-2.0\"}\n{\"name\":\"time\",\"vers\":\"0.2.0\",\"yanked\":false,\"license\":\"GPL-3.0-only\"}\n",
+        )
+        .unwrap();
+        let check = CargoPolicy {
+            index: Some(CratesIndex { root }),
+            ..policy()
+        };
+        let ctx = Context::new(Default::default(), ScopeMode::Staged);
+        let krate = |version: &str| LockedCrate {
+            name: "time".into(),
+            version: version.into(),
+            source: Some("sparse+https://index.crates.io/".into()),
+            line: 1,
+            end_line: 4,
+        };
+        let tags = |version| -> Vec<String> {
+            check
+                .check_crate(&ctx, "Cargo.lock", &krate(version))
+                .into_iter()
+                .map(|f| f.tags[1].clone())
+                .collect()
+        };
+        assert_eq!(tags("0.1.0"), vec!["yanked"]);
+        assert_eq!(tags("0.2.0"), vec!["license"]);
+        assert!(tags("0.3.0").is_empty());
+
+        // Names that are not crate names are not looked up (nor sliced)
+        for name in ["", "éa", "abcé", "../ti/me/time"] {
+            let krate = LockedCrate {
+                name: name.into(),
+                ..krate("0.1.0")
+            };
+            assert!(check.check_crate(&ctx, "Cargo.lock", &krate).is_empty());
+        }
+        assert_eq!(index_path("Serde").as_deref(), Some("se/rd/serde"));
+        assert_eq!(index_path("a").as_deref(), Some("1/a"));
+        assert_eq!(index_path("syn").as_deref(), Some("3/s/syn"));
+    }
+
+    #[test]
+    fn test_manifest_git_rev() {
+        let manifest = r#"[package]
+name = "app"
+
+[dependencies]
+serde = "1"
+pinned = { git = "https://github.com/a/pinned", rev = "abc123" }
+floating = { git = "https://github.com/a/floating", branch = "main" }
+
+[target.'cfg(unix)'.dev-dependencies.tagged]
+git = "https://github.com/a/tagged"
+tag = "v1.0"
+
+[build-dependencies]
+dotted.git = "https://github.com/a/dotted"
+"#;
+        let file = TextFile {
+            path: "Cargo.toml".into(),
+            text: manifest.into(),
+            lines: None,
+        };
+        let ctx = Context::new(Default::default(), ScopeMode::Staged);
+        let findings = policy().check_manifest(&ctx, &file).unwrap();
+        let found: Vec<(u32, &str)> = findings
+            .iter()
+            .map(|f| {
+                (
+                    f.location.as_ref().unwrap().line.unwrap(),
+                    f.message.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (7, "`floating` follows branch \"main\" of https://github.com/a/floating; pin it with `rev`"),
+                (14, "`dotted` follows the default branch of https://github.com/a/dotted; pin it with `rev`"),
+                (9, "`tagged` follows tag \"v1.0\" of https://github.com/a/tagged; pin it with `rev`"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_license_allowed() {
+        let allowed = vec!["MIT".to_string(), "Apache-2.0".to_string()];
+        for ok in [
+            "MIT",
+            "MIT OR GPL-3.0-only",
+            "MIT/Apache-2.0",
+            "(MIT OR GPL-3.0-only) AND Apache-2.0",
+            "Apache-2.0 WITH LLVM-exception",
+        ] {
+            assert!(license_allowed(ok, &allowed), "{}", ok);
+        }
+        for denied in [
+            "GPL-3.0-only",
+            "MIT AND GPL-3.0-only",
+            "(GPL-2.0 OR LGPL-2.1) AND MIT",
+        ] {
+            assert!(!license_allowed(denied, &allowed), "{}", denied);
+        }
+    }
+}
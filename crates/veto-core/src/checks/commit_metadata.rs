@@ -0,0 +1,365 @@
+use anyhow::Result;
+use regex::Regex;
+
+use crate::checks::entropy_guard::{lines_with_offsets, token_location, EntropyGuard};
+use crate::model::Location;
+use crate::targets::{read_commit, scope_commits, CommitInfo, TextFile};
+use crate::{Check, Finding, Severity};
+
+const MESSAGE: &str = "<commit message>";
+const AUTHOR: &str = "<commit author>";
+const COMMITTER: &str = "<commit committer>";
+
+/// Checks the commits in scope (see `targets::scope_commits`) rather than
+/// their files: secrets and forbidden patterns in messages, author/committer
+/// email domains, and an optional required message format.
+///
+/// Each rule has its own severity; `None` disables the rule.
+pub struct CommitMetadata {
+    pub enabled: bool,
+    /// Entropy detection for messages (its own `enabled` flag is ignored).
+    pub entropy: EntropyGuard,
+    /// Regexes that must not appear in a message (e.g. internal hostnames).
+    pub deny_patterns: Vec<Regex>,
+    /// Allowed email domains (subdomains included); empty allows any.
+    pub email_domains: Vec<String>,
+    /// Regex every non-merge commit message must match.
+    pub message_pattern: Option<Regex>,
+    pub secret: Option<Severity>,
+    pub email: Option<Severity>,
+    pub format: Option<Severity>,
+}
+
+impl Check for CommitMetadata {
+    fn id(&self) -> &str {
+        "CM-001"
+    }
+
+    fn description(&self) -> &str {
+        "Checks commit messages and author/committer emails of the commits in scope"
+    }
+
+    fn run(&self, ctx: &crate::Context) -> Result<Vec<Finding>> {
+        if !self.enabled {
+            return Ok(vec![]);
+        }
+
+        let mut findings = vec![];
+        for commit in scope_commits(ctx)? {
+            let info = read_commit(ctx, &commit)?;
+            if let Some(severity) = self.secret {
+                self.check_secrets(ctx, &info, severity, &mut findings);
+            }
+            if let Some(severity) = self.email {
+                self.check_emails(ctx, &info, severity, &mut findings);
+            }
+            if let Some(severity) = self.format {
+                self.check_format(ctx, &info, severity, &mut findings);
+            }
+        }
+        Ok(findings)
+    }
+}
+
+impl CommitMetadata {
+    fn check_secrets(
+        &self,
+        ctx: &crate::Context,
+        info: &CommitInfo,
+        severity: Severity,
+        findings: &mut Vec<Finding>,
+    ) {
+        let message = TextFile {
+            path: MESSAGE.to_string(),
+            text: info.message.clone(),
+            lines: None,
+        };
+        for f in self.entropy.scan_content(ctx, self.id(), &message) {
+            findings.push(Finding {
+                id: self.id().to_string(),
+                fingerprint: ctx.fingerprint(self.id(), &info.id, &f.fingerprint),
+                title: "High-entropy token in commit message".to_string(),
+                severity,
+                location: f.location.map(|l| Location {
+                    commit: Some(info.id.clone()),
+                    ..l
+                }),
+                tags: vec!["commit".to_string(), "entropy".to_string()],
+                ..f
+            });
+        }
+
+        for (line_idx, (line_start, line)) in lines_with_offsets(&info.message).enumerate() {
+            for pattern in &self.deny_patterns {
+                for m in pattern.find_iter(line) {
+                    let mut location = token_location(
+                        MESSAGE,
+                        line_idx + 1,
+                        line_start,
+                        line,
+                        m.start(),
+                        m.as_str(),
+                    );
+                    location.commit = Some(info.id.clone());
+                    findings.push(Finding {
+                        fingerprint: ctx.fingerprint(
+                            self.id(),
+                            &info.id,
+                            &format!("{}\0{}", pattern.as_str(), m.as_str()),
+                        ),
+                        title: "Forbidden pattern in commit message".to_string(),
+                        message: format!(
+                            "Commit message matches `{}`: {}",
+                            pattern.as_str(),
+                            ctx.redaction.redact(m.as_str())
+                        ),
+                        location: Some(location),
+                        ..self.finding(ctx, info, MESSAGE, severity, "pattern")
+                    });
+                }
+            }
+        }
+    }
+
+    fn check_emails(
+        &self,
+        ctx: &crate::Context,
+        info: &CommitInfo,
+        severity: Severity,
+        findings: &mut Vec<Finding>,
+    ) {
+        if self.email_domains.is_empty() {
+            return;
+        }
+        for (file, role, email) in [
+            (AUTHOR, "Author", &info.author_email),
+            (COMMITTER, "Committer", &info.committer_email),
+        ] {
+            let domain = email.rsplit_once('@').map_or("", |(_, d)| d);
+            if domain_allowed(domain, &self.email_domains) {
+                continue;
+            }
+            findings.push(Finding {
+                title: format!("{} email outside allowed domains", role),
+                message: format!(
+                    "{} email domain `{}` is not in commits.email_domains",
+                    role, domain
+                ),
+                ..self.finding(ctx, info, file, severity, "email")
+            });
+        }
+    }
+
+    fn check_format(
+        &self,
+        ctx: &crate::Context,
+        info: &CommitInfo,
+        severity: Severity,
+        findings: &mut Vec<Finding>,
+    ) {
+        let Some(pattern) = &self.message_pattern else {
+            return;
+        };
+        // Merge commits carry generated messages
+        if info.parents > 1 || pattern.is_match(&info.message) {
+            return;
+        }
+        findings.push(Finding {
+            title: "Commit message format".to_string(),
+            message: format!(
+                "Commit message does not match the required format `{}`",
+                pattern.as_str()
+            ),
+            location: Some(Location {
+                line: Some(1),
+                commit: Some(info.id.clone()),
+                ..Location::file(MESSAGE)
+            }),
+            ..self.finding(ctx, info, MESSAGE, severity, "format")
+        });
+    }
+
+    /// A finding located at `file` of commit `info`, fingerprinted by commit and
+    /// `tag`; callers fill in the title and message.
+    fn finding(
+        &self,
+        ctx: &crate::Context,
+        info: &CommitInfo,
+        file: &str,
+        severity: Severity,
+        tag: &str,
+    ) -> Finding {
+        Finding {
+            id: self.id().to_string(),
+            fingerprint: ctx.fingerprint(self.id(), &info.id, &format!("{}\0{}", file, tag)),
+            title: String::new(),
+            message: String::new(),
+            severity,
+            location: Some(Location {
+                commit: Some(info.id.clone()),
+                ..Location::file(file)
+            }),
+            tags: vec!["commit".to_string(), tag.to_string()],
+            snippet: None,
+            details: None,
+        }
+    }
+}
+
+/// Whether `domain` is one of `allowed` or a subdomain of one (case-insensitive).
+fn domain_allowed(domain: &str, allowed: &[String]) -> bool {
+    let domain = domain.to_ascii_lowercase();
+    allowed.iter().any(|a| {
+        let a = a.trim_start_matches('@').to_ascii_lowercase();
+        domain == a || domain.ends_with(&format!(".{}", a))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::benign::{BenignAction, Builtin};
+    use crate::checks::entropy_guard::Thresholds;
+    use crate::examples::Examples;
+    use crate::runner::ScopeMode;
+    use crate::testutil::TestRepo;
+    use crate::Context;
+
+    fn check() -> CommitMetadata {
+        CommitMetadata {
+            enabled: true,
+            entropy: EntropyGuard {
+                enabled: true,
+                min_length: 24,
+                thresholds: Thresholds::from_base(4.2),
+                normalized_threshold: None,
+                classifiers: vec![],
+                allowlist: vec![],
+                max_file_bytes: 1_048_576,
+                max_line_length: 10_000,
+                max_tokens_per_file: 2_000,
+                snippet_context: 0,
+                examples: Examples::default(),
+            },
+            deny_patterns: vec![Regex::new(r"\b[a-z0-9-]+\.internal\.corp\b").unwrap()],
+            email_domains: vec![],
+            message_pattern: Some(Regex::new(r"^(feat|fix): ").unwrap()),
+            secret: Some(Severity::High),
+            email: Some(Severity::Medium),
+            format: Some(Severity::Low),
+        }
+    }
+
+    #[test]
+    fn test_messages() {
+        let repo = TestRepo::new();
+        let leaky = repo.commit(
+            "feat: add client\n\ntoken Zk9xQ2bR7vLp3TnW8yHs4JdK6mXa1cEf\nsee db01.internal.corp\n",
+        );
+        repo.git(&["checkout", "-q", "-b", "side"]);
+        let side = repo.commit("fix: side");
+        repo.git(&["checkout", "-q", "main"]);
+        let wip = repo.commit("wip");
+        repo.git(&[
+            "merge",
+            "-q",
+            "--no-ff",
+            "side",
+            "-m",
+            "Merge branch 'side'",
+        ]);
+        let merge = repo.git(&["rev-parse", "HEAD"]);
+
+        let ctx = Context::new(
+            repo.path().to_path_buf(),
+            ScopeMode::Commits(vec![leaky.clone(), side, wip.clone(), merge]),
+        );
+        let findings = check().run(&ctx).unwrap();
+        let found: Vec<(&str, &str, Option<u32>, Option<u32>)> = findings
+            .iter()
+            .map(|f| {
+                let loc = f.location.as_ref().unwrap();
+                (
+                    loc.commit.as_deref().unwrap(),
+                    f.tags[1].as_str(),
+                    loc.line,
+                    loc.column,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (leaky.as_str(), "entropy", Some(3), Some(7)),
+                (leaky.as_str(), "pattern", Some(4), Some(5)),
+                // The merge commit's generated message is not held to the format
+                (wip.as_str(), "format", Some(1), None),
+            ]
+        );
+        // Matched text only appears redacted
+        assert!(findings
+            .iter()
+            .all(|f| !f.message.contains("Zk9xQ2bR7vLp3TnW8yHs4JdK6mXa1cEf")
+                && !f.message.contains("db01.internal.corp")));
+
+        // `deny_patterns` report at the `secret` severity, and are disabled with it
+        let mut off = check();
+        off.secret = None;
+        assert_eq!(off.run(&ctx).unwrap().len(), 1);
+        off.format = None;
+        assert!(off.run(&ctx).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_dropped_tokens_are_counted_for_cm() {
+        let repo = TestRepo::new();
+        let commit = repo.commit(
+            "fix: revert\n\nThis reverts commit 3f2a9c0d8e7b6a5f4e3d2c1b0a9f8e7d6c5b4a39.\n",
+        );
+        let ctx = Context::new(repo.path().to_path_buf(), ScopeMode::Commits(vec![commit]));
+        let mut check = check();
+        check.entropy.thresholds.hex = 3.0;
+        check.entropy.classifiers = vec![(Builtin::GitSha.shared(), BenignAction::Drop)];
+        assert!(check.run(&ctx).unwrap().is_empty());
+        let dropped = ctx.coverage.take().tokens_dropped;
+        assert_eq!(
+            dropped,
+            vec![crate::model::DroppedTokens {
+                check: "CM-001".to_string(),
+                classifier: "git_sha".to_string(),
+                count: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_emails() {
+        let repo = TestRepo::new();
+        let commit = repo.commit("fix: x");
+        let ctx = Context::new(repo.path().to_path_buf(), ScopeMode::Commits(vec![commit]));
+        let mut check = check();
+        assert!(check.run(&ctx).unwrap().is_empty());
+        check.email_domains = vec!["corp.test".to_string()];
+        let files: Vec<String> = check
+            .run(&ctx)
+            .unwrap()
+            .into_iter()
+            .map(|f| f.location.unwrap().file)
+            .collect();
+        assert_eq!(files, vec![AUTHOR, COMMITTER]);
+        // Staged changes have no commits to check
+        let ctx = Context::new(repo.path().to_path_buf(), ScopeMode::Staged);
+        assert!(check.run(&ctx).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_domain_allowed() {
+        let allowed = vec!["example.com".to_string(), "@corp.test".to_string()];
+        assert!(domain_allowed("example.com", &allowed));
+        assert!(domain_allowed("EU.Example.com", &allowed));
+        assert!(domain_allowed("corp.test", &allowed));
+        assert!(!domain_allowed("notexample.com", &allowed));
+        assert!(!domain_allowed("gmail.com", &allowed));
+        assert!(!domain_allowed("", &allowed));
+    }
+}
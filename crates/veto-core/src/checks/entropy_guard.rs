@@ -1,39 +1,135 @@
 use std::collections::HashMap;
-use std::fs;
-use std::io::Read;
-use std::path::Path;
-use std::process::Command;
+use std::sync::Arc;
 
-use anyhow::{Context as _, Result};
+use anyhow::Result;
 
-use crate::runner::ScopeMode;
-use crate::{Check, Context, Finding, Severity};
+use crate::checks::benign::{BenignAction, Classifier};
+use crate::examples::Examples;
+use crate::model::Location;
+use crate::targets::{get_target_files, TextFile};
+use crate::{Check, Finding, Severity};
 
 pub struct EntropyGuard {
     pub enabled: bool,
     pub min_length: usize,
-    pub threshold: f64,
-    pub ignore_extensions: Vec<String>,
+    /// Shannon entropy (bits/char) a token must exceed, per charset.
+    pub thresholds: Thresholds,
+    /// When set, tokens are judged by their normalised entropy (0..1, see
+    /// `normalized_entropy`) against this instead of by `thresholds`.
+    pub normalized_threshold: Option<f64>,
+    /// Known-benign shapes (git SHAs, UUIDs, ...) checked, in order, for every
+    /// flagged token; the first match decides what happens to it.
+    pub classifiers: Vec<(Arc<dyn Classifier>, BenignAction)>,
     pub allowlist: Vec<String>,
     pub max_file_bytes: u64,
     pub max_line_length: usize,
     pub max_tokens_per_file: usize,
+    /// Characters of context kept on each side of the match in snippets (0 = no snippet).
+    pub snippet_context: usize,
+    /// Configured examples, tested in addition to `BUILTIN_SHOULD_MATCH` / `BUILTIN_SHOULD_NOT_MATCH`.
+    pub examples: Examples,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum CharsetHint {
+// Samples any sensible tuning must keep classifying correctly.
+const BUILTIN_SHOULD_MATCH: &[&str] = &[
+    r#"api_key = "q8Zr2VxN7LmT4pWk9HsC3bYf6GdJ1aQe""#,
+    "AWS_SECRET_ACCESS_KEY=Zk9xQ2bR7vLp3TnW8yHs4JdK6mXa1cEf",
+];
+const BUILTIN_SHOULD_NOT_MATCH: &[&str] = &[
+    "let configuration_manager = ConfigurationManager::new();",
+    "padding = \"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\"",
+    "commit 3f786850e387550fdab836ed7e6dc881de23001b",
+];
+
+// Other runs in a snippet line that look like this are masked too.
+const SNIPPET_REDACT_MIN_LEN: usize = 12;
+const SNIPPET_REDACT_MIN_ENTROPY: f64 = 3.0;
+
+/// Default offset of the hex threshold over the base `threshold`: hex
+/// entropy is capped at 4 bits/char and git hashes / random hex are common.
+pub const HEX_THRESHOLD_BONUS: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CharsetHint {
     Base64ish,
     Base64Urlish,
     Hexish,
     Alnum,
 }
 
+impl CharsetHint {
+    pub const ALL: [CharsetHint; 4] = [
+        CharsetHint::Hexish,
+        CharsetHint::Base64ish,
+        CharsetHint::Base64Urlish,
+        CharsetHint::Alnum,
+    ];
+
+    /// Key of the charset in `[entropy_guard.thresholds]`.
+    pub fn key(self) -> &'static str {
+        match self {
+            CharsetHint::Hexish => "hex",
+            CharsetHint::Base64ish => "base64",
+            CharsetHint::Base64Urlish => "base64url",
+            CharsetHint::Alnum => "alnum",
+        }
+    }
+
+    /// Number of distinct characters tokens of this charset draw from.
+    fn alphabet_size(self) -> usize {
+        match self {
+            CharsetHint::Hexish => 16,
+            CharsetHint::Base64ish | CharsetHint::Base64Urlish => 64,
+            CharsetHint::Alnum => 62,
+        }
+    }
+}
+
+/// Entropy thresholds (bits/char) per `CharsetHint`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    pub hex: f64,
+    pub base64: f64,
+    pub base64url: f64,
+    pub alnum: f64,
+}
+
+impl Thresholds {
+    /// `threshold` for every charset, plus `HEX_THRESHOLD_BONUS` for hex.
+    pub fn from_base(threshold: f64) -> Self {
+        Self {
+            hex: threshold + HEX_THRESHOLD_BONUS,
+            base64: threshold,
+            base64url: threshold,
+            alnum: threshold,
+        }
+    }
+
+    pub fn get(&self, charset: CharsetHint) -> f64 {
+        match charset {
+            CharsetHint::Hexish => self.hex,
+            CharsetHint::Base64ish => self.base64,
+            CharsetHint::Base64Urlish => self.base64url,
+            CharsetHint::Alnum => self.alnum,
+        }
+    }
+
+    pub fn set(&mut self, charset: CharsetHint, threshold: f64) {
+        match charset {
+            CharsetHint::Hexish => self.hex = threshold,
+            CharsetHint::Base64ish => self.base64 = threshold,
+            CharsetHint::Base64Urlish => self.base64url = threshold,
+            CharsetHint::Alnum => self.alnum = threshold,
+        }
+    }
+}
+
 impl Check for EntropyGuard {
-    fn id(&self) -> &'static str {
+    fn id(&self) -> &str {
         "EG-001"
     }
 
-    fn description(&self) -> &'static str {
+    fn description(&self) -> &str {
         "Detects high-entropy strings that may be secrets"
     }
 
@@ -45,224 +141,181 @@ impl Check for EntropyGuard {
         let files = get_target_files(ctx, self.max_file_bytes)?;
         let mut findings = vec![];
 
-        for (path_string, content) in files {
-            // Check ignore extensions
-            if let Some(ext) = Path::new(&path_string).extension() {
-                if let Some(ext_str) = ext.to_str() {
-                    if self
-                        .ignore_extensions
-                        .iter()
-                        .any(|e| e.eq_ignore_ascii_case(ext_str))
-                    {
-                        continue;
-                    }
-                }
-            }
-
-            // Allowlist check (naive substring match for file path, maybe?)
-            // Usually allowlist is for token content, but file path ignore is also useful.
-            // For now, let's implement token-based allowlist as requested.
+        for file in files.iter() {
+            findings.extend(self.scan_content(ctx, self.id(), file));
+        }
 
-            let mut token_count = 0;
-            for (line_idx, line) in content.lines().enumerate() {
-                // DoS protection: Skip very long lines
-                if line.len() > self.max_line_length {
-                    continue;
-                }
+        Ok(findings)
+    }
 
-                let line_num = line_idx + 1;
-                // Runs extraction
-                for (token, charset) in extract_runs(line) {
-                    token_count += 1;
-                    if token_count > self.max_tokens_per_file {
-                        break;
-                    }
+    fn examples(&self) -> Examples {
+        Examples::new(BUILTIN_SHOULD_MATCH, BUILTIN_SHOULD_NOT_MATCH).chain(&self.examples)
+    }
 
-                    if token.len() < self.min_length {
-                        continue;
-                    }
+    fn fires_on(&self, sample: &str) -> Option<bool> {
+        Some(sample.lines().any(|line| {
+            extract_runs(line)
+                .into_iter()
+                .any(|(token, charset, start)| {
+                    self.flagged_entropy(token, charset).is_some()
+                        && !self.dropped(line, start, token)
+                })
+        }))
+    }
+}
 
-                    if self.allowlist.iter().any(|pattern| token.contains(pattern)) {
-                        continue;
-                    }
+impl EntropyGuard {
+    /// Entropy and normalised entropy of `token` if it is flagged as a likely secret.
+    fn flagged_entropy(&self, token: &str, charset: CharsetHint) -> Option<(f64, f64)> {
+        if !self.is_candidate(token) {
+            return None;
+        }
+        let entropy = shannon_entropy(token);
+        let normalized = normalized_entropy(entropy, charset, token.len());
+        let flagged = match self.normalized_threshold {
+            Some(threshold) => normalized > threshold,
+            None => entropy > self.thresholds.get(charset),
+        };
+        flagged.then_some((entropy, normalized))
+    }
 
-                    // Unique chars filter (heuristic)
-                    if count_unique_chars(token) < 6 {
-                        continue;
-                    }
+    /// Whether `token` is long, varied and unallowlisted enough to be judged by
+    /// its entropy at all.
+    fn is_candidate(&self, token: &str) -> bool {
+        token.len() >= self.min_length
+            && !self.allowlist.iter().any(|pattern| token.contains(pattern))
+            // Unique chars filter (heuristic)
+            && count_unique_chars(token) >= 6
+    }
 
-                    // Heuristics adjustments
-                    let mut threshold = self.threshold;
-                    if let CharsetHint::Hexish = charset {
-                        // Hex needs higher entropy or it flags too many git hashes / random hex
-                        threshold += 0.5;
-                    }
+    /// Tokens of `line` that are judged by their entropy, with their charset.
+    pub(crate) fn candidates<'a>(&self, line: &'a str) -> Vec<(&'a str, CharsetHint)> {
+        extract_runs(line)
+            .into_iter()
+            .filter(|&(token, _, start)| {
+                self.is_candidate(token) && !self.dropped(line, start, token)
+            })
+            .map(|(token, charset, _)| (token, charset))
+            .collect()
+    }
 
-                    let entropy = shannon_entropy(token);
-                    if entropy > threshold {
-                        findings.push(Finding {
-                            id: self.id().to_string(),
-                            title: "High-entropy token detected".to_string(),
-                            severity: Severity::High,
-                            message: format!(
-                                "Possible secret detected (entropy: {:.2}, len: {}). Content: {}",
-                                entropy,
-                                token.len(),
-                                mask_token(token)
-                            ),
-                            location: Some(crate::model::Location {
-                                file: path_string.clone(),
-                                line: Some(line_num as u32),
-                            }),
-                            tags: vec!["entropy".to_string()],
-                            details: Some(crate::model::FindingDetails {
-                                entropy,
-                                token_len: token.len(),
-                                charset: format!("{:?}", charset),
-                            }),
-                        });
-                    }
-                }
-                if token_count > self.max_tokens_per_file {
-                    break;
-                }
-            }
-        }
+    /// The first classifier recognising `token` (at byte `start` of `line`) as benign.
+    fn classify(
+        &self,
+        line: &str,
+        start: usize,
+        token: &str,
+    ) -> Option<(&dyn Classifier, BenignAction)> {
+        self.classifiers
+            .iter()
+            .find(|(c, _)| c.matches(line, start, token))
+            .map(|(c, action)| (c.as_ref(), *action))
+    }
 
-        Ok(findings)
+    fn dropped(&self, line: &str, start: usize, token: &str) -> bool {
+        matches!(
+            self.classify(line, start, token),
+            Some((_, BenignAction::Drop))
+        )
     }
-}
 
-fn get_target_files(ctx: &Context, max_file_bytes: u64) -> Result<Vec<(String, String)>> {
-    match ctx.scope {
-        ScopeMode::Staged => {
-            // git diff --cached --name-only --diff-filter=AM
-            let output = Command::new("git")
-                .arg("diff")
-                .arg("--cached")
-                .arg("--name-only")
-                .arg("--diff-filter=AM")
-                .current_dir(&ctx.repo_root)
-                .output()
-                .context("git diff --cached failed")?;
-
-            if !output.status.success() {
-                return Err(anyhow::anyhow!(
-                    "git diff failed: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                ));
-            }
+    /// Scans one file's text (only the lines in scope); coverage of partial scans is
+    /// recorded in `ctx.coverage`, and dropped tokens are counted for `check` (the
+    /// id of the check doing the scan).
+    pub(crate) fn scan_content(
+        &self,
+        ctx: &crate::Context,
+        check: &str,
+        file: &TextFile,
+    ) -> Vec<Finding> {
+        let path_string = file.path.as_str();
+        let mut findings = vec![];
 
-            let paths = String::from_utf8(output.stdout)?;
-            let mut results = vec![];
-
-            for path in paths.lines() {
-                // Check size first: git cat-file -s :path
-                let size_out = Command::new("git")
-                    .arg("cat-file")
-                    .arg("-s")
-                    .arg(format!(":{}", path))
-                    .current_dir(&ctx.repo_root)
-                    .output();
-
-                if let Ok(so) = size_out {
-                    if so.status.success() {
-                        let size_str = String::from_utf8_lossy(&so.stdout);
-                        if let Ok(size) = size_str.trim().parse::<u64>() {
-                            if size > max_file_bytes {
-                                // Skip
-                                continue;
-                            }
-                        }
-                    }
-                }
+        // Allowlist check (naive substring match for file path, maybe?)
+        // Usually allowlist is for token content, but file path ignore is also useful.
+        // For now, let's implement token-based allowlist as requested.
 
-                // git show :path
-                let show_out = Command::new("git")
-                    .arg("show")
-                    .arg(format!(":{}", path))
-                    .current_dir(&ctx.repo_root)
-                    .output();
-
-                if let Ok(out) = show_out {
-                    if out.status.success() {
-                        // Check binary matches
-                        if is_binary(&out.stdout) {
-                            continue;
-                        }
-                        if let Ok(s) = String::from_utf8(out.stdout) {
-                            results.push((path.to_string(), s));
-                        }
-                    }
-                }
+        let mut token_count = 0;
+        for (line_idx, (line_start, line)) in lines_with_offsets(&file.text).enumerate() {
+            if !file.includes_line(line_idx + 1) {
+                continue;
             }
-            Ok(results)
-        }
-        ScopeMode::Worktree => {
-            let output = Command::new("git")
-                .arg("diff")
-                .arg("--name-only")
-                .arg("--diff-filter=AM")
-                .current_dir(&ctx.repo_root)
-                .output()
-                .context("git diff failed")?;
-
-            if !output.status.success() {
-                return Err(anyhow::anyhow!(
-                    "git diff failed: {}",
-                    String::from_utf8_lossy(&output.stderr)
-                ));
-            }
-
-            let paths = String::from_utf8(output.stdout)?;
-            let mut results = vec![];
-
-            for p in paths.lines() {
-                let full_path = ctx.repo_root.join(p);
-                if full_path.exists() && full_path.is_file() {
-                    // Check size: fs::metadata
-                    if let Ok(meta) = fs::metadata(&full_path) {
-                        if meta.len() > max_file_bytes {
-                            continue;
-                        }
-                    }
 
-                    // Read file
-                    let f = fs::File::open(&full_path)?;
-                    let mut buffer = Vec::new();
-                    // Double check with take to prevent TOCTOU race where file grows?
-                    // Or just read max + 1
-                    f.take(max_file_bytes + 1).read_to_end(&mut buffer)?;
+            // DoS protection: Skip very long lines
+            if line.len() > self.max_line_length {
+                ctx.coverage.line_skipped(path_string, line_idx + 1);
+                continue;
+            }
 
-                    if buffer.len() as u64 > max_file_bytes {
-                        continue;
-                    }
+            let line_num = line_idx + 1;
+            // Runs extraction
+            for (token, charset, start) in extract_runs(line) {
+                token_count += 1;
+                if token_count > self.max_tokens_per_file {
+                    break;
+                }
 
-                    if is_binary(&buffer) {
+                if let Some((entropy, normalized)) = self.flagged_entropy(token, charset) {
+                    let benign = self.classify(line, start, token);
+                    if let Some((classifier, BenignAction::Drop)) = benign {
+                        ctx.coverage.token_dropped(check, classifier.name());
                         continue;
                     }
-
-                    if let Ok(s) = String::from_utf8(buffer) {
-                        results.push((p.to_string(), s));
+                    let demoted_by = benign.map(|(c, _)| c);
+                    let mut tags = vec!["entropy".to_string()];
+                    if demoted_by.is_some() {
+                        tags.push("benign".to_string());
                     }
+                    findings.push(Finding {
+                        id: self.id().to_string(),
+                        fingerprint: ctx.fingerprint(self.id(), path_string, token),
+                        title: match demoted_by {
+                            Some(c) => format!("High-entropy token (likely {})", c.description()),
+                            None => "High-entropy token detected".to_string(),
+                        },
+                        severity: if demoted_by.is_some() {
+                            Severity::Low
+                        } else {
+                            Severity::High
+                        },
+                        message: format!(
+                            "Possible secret detected (entropy: {:.2}, len: {}). Content: {}",
+                            entropy,
+                            token.len(),
+                            ctx.redaction.redact(token)
+                        ),
+                        location: Some(token_location(
+                            path_string,
+                            line_num,
+                            line_start,
+                            line,
+                            start,
+                            token,
+                        )),
+                        tags,
+                        snippet: (self.snippet_context > 0)
+                            .then(|| masked_snippet(ctx, line, start, token, self.snippet_context)),
+                        details: Some(crate::model::FindingDetails {
+                            entropy,
+                            normalized_entropy: normalized,
+                            token_len: token.len(),
+                            charset: format!("{:?}", charset),
+                            classifier: demoted_by.map(|c| c.name().to_string()),
+                        }),
+                    });
                 }
             }
-            Ok(results)
-        }
-        ScopeMode::Repo => {
-            // TODO: Phase 2
-            Ok(vec![])
+            if token_count > self.max_tokens_per_file {
+                ctx.coverage.token_limit_reached(path_string);
+                break;
+            }
         }
-    }
-}
 
-// Simple heuristic for binary content
-fn is_binary(data: &[u8]) -> bool {
-    // Check first 1024 bytes for null byte
-    data.iter().take(1024).any(|&b| b == 0)
+        findings
+    }
 }
 
-fn shannon_entropy(s: &str) -> f64 {
+pub fn shannon_entropy(s: &str) -> f64 {
     let mut map = HashMap::new();
     let len = s.len() as f64;
     for c in s.chars() {
@@ -277,9 +330,42 @@ fn shannon_entropy(s: &str) -> f64 {
     entropy
 }
 
-// Extract runs of allowed chars.
+/// Like `str::lines`, but also yields the byte offset at which each line starts.
+pub(crate) fn lines_with_offsets(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0;
+    content.split_inclusive('\n').map(move |raw| {
+        let start = offset;
+        offset += raw.len();
+        let line = raw.strip_suffix('\n').unwrap_or(raw);
+        (start, line.strip_suffix('\r').unwrap_or(line))
+    })
+}
+
+/// Location of `token`, found at byte `start` of `line` (which starts at byte `line_start` of the file).
+pub(crate) fn token_location(
+    file: &str,
+    line_num: usize,
+    line_start: usize,
+    line: &str,
+    start: usize,
+    token: &str,
+) -> Location {
+    let column = line[..start].chars().count() + 1;
+    Location {
+        file: file.to_string(),
+        line: Some(line_num as u32),
+        column: Some(column as u32),
+        end_line: Some(line_num as u32),
+        end_column: Some((column + token.chars().count()) as u32),
+        start_byte: Some((line_start + start) as u64),
+        end_byte: Some((line_start + start + token.len()) as u64),
+        commit: None,
+    }
+}
+
+// Extract runs of allowed chars, with the byte offset of each run within the line.
 // Allowed = [A-Za-z0-9+/=_-]
-fn extract_runs(line: &str) -> Vec<(&str, CharsetHint)> {
+pub(crate) fn extract_runs(line: &str) -> Vec<(&str, CharsetHint, usize)> {
     let mut results = vec![];
     let mut start = None;
 
@@ -295,13 +381,13 @@ fn extract_runs(line: &str) -> Vec<(&str, CharsetHint)> {
             }
         } else if let Some(s) = start {
             let token = &line[s..i];
-            results.push((token, detect_charset(token)));
+            results.push((token, detect_charset(token), s));
             start = None;
         }
     }
     if let Some(s) = start {
         let token = &line[s..];
-        results.push((token, detect_charset(token)));
+        results.push((token, detect_charset(token), s));
     }
     results
 }
@@ -323,6 +409,18 @@ fn detect_charset(s: &str) -> CharsetHint {
     }
 }
 
+/// `entropy` as a fraction of the most a `len`-char token of `charset` can
+/// have: log2 of the alphabet size, or of `len` if shorter. Comparable across
+/// charsets, unlike raw entropy (hex caps at 4 bits/char, base64 at 6).
+pub fn normalized_entropy(entropy: f64, charset: CharsetHint, len: usize) -> f64 {
+    let max = (charset.alphabet_size().min(len) as f64).log2();
+    if max > 0.0 {
+        entropy / max
+    } else {
+        0.0
+    }
+}
+
 fn count_unique_chars(s: &str) -> usize {
     let mut chars = s.chars().collect::<Vec<_>>();
     chars.sort();
@@ -330,18 +428,68 @@ fn count_unique_chars(s: &str) -> usize {
     chars.len()
 }
 
-fn mask_token(token: &str) -> String {
-    if token.len() <= 8 {
-        return "***".to_string();
+/// The line around the token at byte `start`, with the token redacted and every
+/// other secret-looking run replaced by `***`. The token need not be a single
+/// run (custom rules match arbitrary text). Context is trimmed to `width`
+/// characters on each side of the masked token; masking happens before
+/// trimming, so a cut never exposes part of a secret.
+pub(crate) fn masked_snippet(
+    ctx: &crate::Context,
+    line: &str,
+    start: usize,
+    token: &str,
+    width: usize,
+) -> String {
+    let end = start + token.len();
+    let mut spans = vec![(start, end, ctx.redaction.redact(token))];
+    for (run, _, run_start) in extract_runs(line) {
+        let overlaps_token = run_start < end && run_start + run.len() > start;
+        if !overlaps_token
+            && run.len() >= SNIPPET_REDACT_MIN_LEN
+            && shannon_entropy(run) > SNIPPET_REDACT_MIN_ENTROPY
+        {
+            spans.push((run_start, run_start + run.len(), "***".to_string()));
+        }
+    }
+    spans.sort_by_key(|&(from, _, _)| from);
+
+    let mut masked = String::new();
+    let mut cursor = 0;
+    let mut mask_range = (0, 0);
+    for (from, to, replacement) in spans {
+        masked.push_str(&line[cursor..from]);
+        if from == start {
+            let at = masked.chars().count();
+            mask_range = (at, at + replacement.chars().count());
+        }
+        masked.push_str(&replacement);
+        cursor = to;
     }
-    let start = &token[..4];
-    let end = &token[token.len() - 4..];
-    format!("{}...{}", start, end)
+    masked.push_str(&line[cursor..]);
+
+    let chars: Vec<char> = masked
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    let from = mask_range.0.saturating_sub(width);
+    let to = (mask_range.1 + width).min(chars.len());
+    let mut snippet: String = chars[from..to].iter().collect();
+    snippet = snippet.trim().to_string();
+    if from > 0 {
+        snippet.insert(0, '…');
+    }
+    if to < chars.len() {
+        snippet.push('…');
+    }
+    snippet
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::redact::Redaction;
+    use crate::runner::ScopeMode;
+    use crate::Context;
 
     #[test]
     fn test_entropy() {
@@ -355,10 +503,168 @@ mod tests {
         assert!(e_high > 3.0); // usually > 3.5 for this length
     }
 
+    fn guard() -> EntropyGuard {
+        EntropyGuard {
+            enabled: true,
+            min_length: 24,
+            thresholds: Thresholds::from_base(4.2),
+            normalized_threshold: None,
+            classifiers: vec![],
+            allowlist: vec![],
+            max_file_bytes: 1_048_576,
+            max_line_length: 10_000,
+            max_tokens_per_file: 2_000,
+            snippet_context: 40,
+            examples: Examples::default(),
+        }
+    }
+
+    #[test]
+    fn test_builtin_examples() {
+        let report = crate::examples::test_examples(&guard()).unwrap();
+        assert_eq!(report.failures, vec![]);
+
+        // Detuning is caught
+        let strict = EntropyGuard {
+            thresholds: Thresholds::from_base(5.5),
+            ..guard()
+        };
+        let report = crate::examples::test_examples(&strict).unwrap();
+        assert_eq!(report.failures.len(), BUILTIN_SHOULD_MATCH.len());
+        assert!(report.failures.iter().all(|f| f.should_match));
+    }
+
+    #[test]
+    fn test_charset_thresholds() {
+        // 40 hex chars, 16 distinct: 4.0 bits/char, the hex maximum
+        let hex_key = "1f3a9c0e7b5d2486fa0c9e1b3d5a7f2c4e6b8d01";
+        let entropy = shannon_entropy(hex_key);
+        assert!(entropy <= 4.0 && entropy > 3.9);
+        assert!(
+            (normalized_entropy(entropy, CharsetHint::Hexish, 40) - entropy / 4.0).abs() < 1e-9
+        );
+
+        // Never flagged at the default hex threshold (4.2 + 0.5)...
+        assert_eq!(guard().fires_on(hex_key), Some(false));
+        // ...but is with a hex-specific one, or by normalised entropy
+        let mut hex = guard();
+        hex.thresholds.hex = 3.8;
+        assert_eq!(hex.fires_on(hex_key), Some(true));
+        // The git SHA classifier does not swallow it: a git SHA needs git context
+        hex.classifiers = vec![(
+            crate::checks::benign::Builtin::GitSha.shared(),
+            BenignAction::Drop,
+        )];
+        let line = format!("api_key = \"{}\"", hex_key);
+        assert_eq!(hex.fires_on(&line), Some(true));
+        assert_eq!(hex.fires_on(&format!("commit {}", hex_key)), Some(false));
+        let normalized = EntropyGuard {
+            normalized_threshold: Some(0.9),
+            ..guard()
+        };
+        assert_eq!(normalized.fires_on(hex_key), Some(true));
+        assert_eq!(
+            normalized.fires_on("let configuration_manager_instance = 1;"),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_benign_classifiers() {
+        use crate::checks::benign::Builtin;
+
+        let data = "Zk9xQ2bR7vLp3TnW8yHs4JdK6mXa1cEf+/q8Zr2VxN7=";
+        let file = TextFile {
+            path: "index.html".into(),
+            text: format!("<img src=\"data:image/png;base64,{}\">\n", data),
+            lines: None,
+        };
+        let ctx = Context::new(Default::default(), ScopeMode::Staged);
+        assert_eq!(guard().scan_content(&ctx, "EG-001", &file).len(), 1);
+
+        let with = |action| EntropyGuard {
+            classifiers: vec![(Builtin::DataUri.shared(), action)],
+            ..guard()
+        };
+        assert!(with(BenignAction::Drop)
+            .scan_content(&ctx, "EG-001", &file)
+            .is_empty());
+        let dropped = ctx.coverage.take().tokens_dropped;
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(
+            (dropped[0].classifier.as_str(), dropped[0].count),
+            ("data_uri", 1)
+        );
+
+        let findings = with(BenignAction::Demote).scan_content(&ctx, "EG-001", &file);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Low);
+        assert!(findings[0].tags.contains(&"benign".to_string()));
+        let details = findings[0].details.as_ref().unwrap();
+        assert_eq!(details.classifier.as_deref(), Some("data_uri"));
+    }
+
+    #[test]
+    fn test_no_raw_token_in_output() {
+        let secret = "Zk9xQ2bR7vLp3TnW8yHs4JdK6mXa";
+        let neighbour = "Qm8vN3xT6rB1kL9pW4sZ7hJ2cF5d";
+        let content = format!(
+            "a = \"{}\"\nb = [\"{}\", \"{}\"]\n",
+            secret, secret, neighbour
+        );
+
+        let mut file = TextFile {
+            path: "config.py".into(),
+            text: content,
+            lines: None,
+        };
+        for redaction in [
+            Redaction::Partial,
+            Redaction::Full,
+            Redaction::Hash {
+                salt: "pepper".into(),
+            },
+            Redaction::Length,
+        ] {
+            let mut ctx = Context::new(Default::default(), ScopeMode::Staged);
+            ctx.redaction = redaction.clone();
+            let findings = guard().scan_content(&ctx, "EG-001", &file);
+            assert_eq!(findings.len(), 3);
+
+            let json = serde_json::to_string(&findings).unwrap();
+            for raw in [secret, neighbour] {
+                assert!(!json.contains(raw), "{:?} leaked {}", redaction, raw);
+                if redaction != Redaction::Partial {
+                    assert!(!json.contains(&raw[..4]), "{:?} leaked a prefix", redaction);
+                }
+            }
+        }
+
+        // Only lines in scope (e.g. added by a diff) are scanned
+        file.lines = Some(vec![(2, 2)]);
+        let ctx = Context::new(Default::default(), ScopeMode::Staged);
+        let findings = guard().scan_content(&ctx, "EG-001", &file);
+        assert_eq!(findings.len(), 2);
+        assert!(findings
+            .iter()
+            .all(|f| f.location.as_ref().unwrap().line == Some(2)));
+    }
+
     #[test]
-    fn test_mask() {
-        assert_eq!(mask_token("secret"), "***");
-        assert_eq!(mask_token("1234567890"), "1234...7890");
+    fn test_masked_snippet() {
+        let secret = "Zk9xQ2bR7vLp3TnW8yHs4JdK6mXa";
+        let other = "pQ7sT2vX9zB4nM6k";
+        let line = format!("\tlet key = \"{}\"; // was {}", secret, other);
+        let start = line.find(secret).unwrap();
+
+        let ctx = Context::new(Default::default(), ScopeMode::Staged);
+        let snippet = masked_snippet(&ctx, &line, start, secret, 80);
+        assert_eq!(snippet, "let key = \"Zk9x...6mXa\"; // was ***");
+        assert!(!snippet.contains(other));
+
+        // Narrow context trims around the masked token, never inside a secret
+        let snippet = masked_snippet(&ctx, &line, start, secret, 3);
+        assert_eq!(snippet, "…= \"Zk9x...6mXa\";…");
     }
 
     #[test]
@@ -392,6 +698,25 @@ mod tests {
         matches!(runs[0].1, CharsetHint::Base64ish);
     }
 
+    #[test]
+    fn test_token_location() {
+        let content = "first\r\nkey = \"é-tok\"\n";
+        let lines: Vec<_> = lines_with_offsets(content).collect();
+        assert_eq!(lines, vec![(0, "first"), (7, "key = \"é-tok\"")]);
+
+        let (line_start, line) = lines[1];
+        let (token, _, start) = extract_runs(line)[2];
+        assert_eq!(token, "-tok");
+        let loc = token_location("a.txt", 2, line_start, line, start, token);
+        assert_eq!(loc.column, Some(9)); // counted in chars, not bytes
+        assert_eq!(loc.end_column, Some(13));
+        let (s, e) = (
+            loc.start_byte.unwrap() as usize,
+            loc.end_byte.unwrap() as usize,
+        );
+        assert_eq!(&content[s..e], "-tok");
+    }
+
     #[test]
     fn test_detect_charset() {
         assert!(matches!(
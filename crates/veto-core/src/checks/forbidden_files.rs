@@ -0,0 +1,146 @@
+use anyhow::Result;
+
+use crate::examples::Examples;
+use crate::glob::PathGlobs;
+use crate::model::Location;
+use crate::targets::list_paths;
+use crate::{Check, Finding, Severity};
+
+/// Flags paths that must never be committed (`.env`, `*.pem`, `terraform.tfstate`, ...).
+///
+/// Only the path is evaluated, so files skipped by content checks (too large,
+/// binary, non-UTF-8) are still caught, as are paths `[ignore]` hides from them
+/// (a `.env` under `vendor/` is still a `.env`).
+pub struct ForbiddenFiles {
+    pub enabled: bool,
+    pub patterns: PathGlobs,
+    pub allow: PathGlobs,
+    pub severity: Severity,
+    /// Configured example paths, tested in addition to the built-in ones.
+    pub examples: Examples,
+}
+
+impl Check for ForbiddenFiles {
+    fn id(&self) -> &str {
+        "FF-001"
+    }
+
+    fn description(&self) -> &str {
+        "Blocks files that must never be committed (keys, env files, state files)"
+    }
+
+    fn run(&self, ctx: &crate::Context) -> Result<Vec<Finding>> {
+        if !self.enabled {
+            return Ok(vec![]);
+        }
+
+        let paths = list_paths(ctx)?;
+        Ok(paths
+            .into_iter()
+            .filter_map(|path| self.evaluate(ctx, &path))
+            .collect())
+    }
+
+    fn examples(&self) -> Examples {
+        Examples::new(&[".env", "deploy/id_rsa"], &["src/main.rs", ".env.example"])
+            .chain(&self.examples)
+    }
+
+    fn fires_on(&self, path: &str) -> Option<bool> {
+        Some(self.forbidden_pattern(path).is_some())
+    }
+}
+
+impl ForbiddenFiles {
+    /// The forbidden pattern `path` matches, unless it is allowed.
+    fn forbidden_pattern(&self, path: &str) -> Option<&str> {
+        if self.allow.is_match(path) {
+            return None;
+        }
+        self.patterns.matched(path)
+    }
+
+    fn evaluate(&self, ctx: &crate::Context, path: &str) -> Option<Finding> {
+        let pattern = self.forbidden_pattern(path)?;
+        Some(Finding {
+            id: self.id().to_string(),
+            fingerprint: ctx.fingerprint(self.id(), path, ""),
+            title: "Forbidden file".to_string(),
+            message: format!(
+                "File matches forbidden pattern `{}` and must not be committed",
+                pattern
+            ),
+            severity: self.severity,
+            location: Some(Location::file(path)),
+            tags: vec!["forbidden-file".to_string()],
+            snippet: None,
+            details: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::ScopeMode;
+    use crate::testutil::TestRepo;
+    use crate::Context;
+
+    fn ctx() -> Context {
+        Context::new(Default::default(), ScopeMode::Staged)
+    }
+
+    fn guard(patterns: &[&str], allow: &[&str]) -> ForbiddenFiles {
+        let to_vec = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        ForbiddenFiles {
+            enabled: true,
+            patterns: PathGlobs::new(&to_vec(patterns)).unwrap(),
+            allow: PathGlobs::new(&to_vec(allow)).unwrap(),
+            severity: Severity::High,
+            examples: Examples::default(),
+        }
+    }
+
+    #[test]
+    fn test_file_name_patterns_match_at_any_depth() {
+        let g = guard(&[".env", "*.pem", "terraform.tfstate"], &[]);
+        assert!(g.evaluate(&ctx(), ".env").is_some());
+        assert!(g.evaluate(&ctx(), "services/api/.env").is_some());
+        assert!(g.evaluate(&ctx(), "certs/server.pem").is_some());
+        assert!(g.evaluate(&ctx(), "infra/terraform.tfstate").is_some());
+        assert!(g.evaluate(&ctx(), ".env.example").is_none());
+        assert!(g.evaluate(&ctx(), "src/main.rs").is_none());
+    }
+
+    #[test]
+    fn test_path_patterns_and_allow() {
+        let g = guard(&["secrets/**", ".env.*"], &[".env.example"]);
+        assert!(g.evaluate(&ctx(), "secrets/prod/db.txt").is_some());
+        assert!(g.evaluate(&ctx(), "app/secrets/db.txt").is_none());
+        assert!(g.evaluate(&ctx(), ".env.local").is_some());
+        assert!(g.evaluate(&ctx(), "web/.env.example").is_none());
+    }
+
+    #[test]
+    fn test_double_star_path_patterns_match_at_any_depth() {
+        let g = guard(&["**/.kube/config"], &[]);
+        assert!(g.evaluate(&ctx(), ".kube/config").is_some());
+        assert!(g.evaluate(&ctx(), "infra/.kube/config").is_some());
+        assert!(g.evaluate(&ctx(), "infra/.kube/config.bak").is_none());
+    }
+
+    #[test]
+    fn test_ignored_paths_are_checked() {
+        let repo = TestRepo::new();
+        repo.write("vendor/lib/.env", "KEY=1\n");
+        repo.git(&["add", "-A"]);
+        let mut ctx = Context::new(repo.path().to_path_buf(), ScopeMode::Staged);
+        ctx.ignore = PathGlobs::new(&["**/vendor/**".to_string()]).unwrap();
+        let findings = guard(&[".env"], &[]).run(&ctx).unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].location.as_ref().unwrap().file,
+            "vendor/lib/.env"
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::Result;
+
+use crate::encoding::is_binary;
+use crate::glob::PathGlobs;
+use crate::model::Location;
+use crate::runner::ScopeMode;
+use crate::targets::{attr_values, list_all_targets, read_target, target_size};
+use crate::{Check, Finding, Severity};
+
+const LFS_POINTER_PREFIX: &[u8] = b"version https://git-lfs.github.com/spec/v1";
+const SNIFF_BYTES: u64 = 8_000;
+
+/// Gates what kind of blobs may enter the repository: oversized files,
+/// binaries outside allowed paths, and files that belong in Git LFS.
+///
+/// Each rule has its own severity; `None` disables the rule.
+pub struct LargeFiles {
+    pub enabled: bool,
+    pub max_bytes: u64,
+    pub binary_allow: PathGlobs,
+    pub oversize: Option<Severity>,
+    pub binary: Option<Severity>,
+    pub lfs: Option<Severity>,
+}
+
+impl Check for LargeFiles {
+    fn id(&self) -> &str {
+        "LF-001"
+    }
+
+    fn description(&self) -> &str {
+        "Flags oversized files, unexpected binaries and LFS files committed as regular blobs"
+    }
+
+    fn run(&self, ctx: &crate::Context) -> Result<Vec<Finding>> {
+        if !self.enabled {
+            return Ok(vec![]);
+        }
+
+        // `[ignore]` hides files from content scans, not from this gate
+        let targets = list_all_targets(ctx)?;
+        let paths: Vec<String> = targets.iter().map(|t| t.path.clone()).collect();
+        // Worktree files are smudged: an LFS file on disk holds its real
+        // content, and only becomes a pointer when `git add` runs the clean filter
+        let worktree = matches!(ctx.scope, ScopeMode::Worktree);
+        let lfs_paths = if self.lfs.is_some() || worktree {
+            attr_values(ctx, "filter", &paths)?
+        } else {
+            Default::default()
+        };
+        let mut findings = vec![];
+
+        for target in &targets {
+            let path = &target.path;
+            let tracked = lfs_paths.get(path.as_str()).map(String::as_str) == Some("lfs");
+            if worktree && tracked {
+                continue;
+            }
+            let size = target_size(ctx, target);
+            let head = match read_target(ctx, target, SNIFF_BYTES)? {
+                Some(head) => head,
+                None => continue,
+            };
+            let is_lfs_pointer = head.starts_with(LFS_POINTER_PREFIX);
+
+            if let (Some(severity), Some(size)) = (self.oversize, size) {
+                if size > self.max_bytes {
+                    findings.push(self.finding(
+                        ctx,
+                        path,
+                        severity,
+                        "Large file",
+                        format!(
+                            "File is {} bytes, above the {} byte limit",
+                            size, self.max_bytes
+                        ),
+                        "large-file",
+                    ));
+                }
+            }
+
+            if let Some(severity) = self.binary {
+                if !is_lfs_pointer && is_binary(&head) && !self.binary_allow.is_match(path) {
+                    findings.push(self.finding(
+                        ctx,
+                        path,
+                        severity,
+                        "Binary file",
+                        "Binary content outside of allowed paths/extensions".to_string(),
+                        "binary",
+                    ));
+                }
+            }
+
+            if let Some(severity) = self.lfs {
+                if tracked && !is_lfs_pointer {
+                    findings.push(self.finding(
+                        ctx,
+                        path,
+                        severity,
+                        "File not stored in Git LFS",
+                        "Path has `filter=lfs` in .gitattributes but was committed as a regular blob"
+                            .to_string(),
+                        "lfs",
+                    ));
+                }
+            }
+        }
+
+        Ok(findings)
+    }
+}
+
+impl LargeFiles {
+    fn finding(
+        &self,
+        ctx: &crate::Context,
+        path: &str,
+        severity: Severity,
+        title: &str,
+        message: String,
+        tag: &str,
+    ) -> Finding {
+        Finding {
+            id: self.id().to_string(),
+            fingerprint: ctx.fingerprint(self.id(), path, tag),
+            title: title.to_string(),
+            message,
+            severity,
+            location: Some(Location::file(path)),
+            tags: vec![tag.to_string()],
+            snippet: None,
+            details: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::TestRepo;
+    use crate::Context;
+
+    const POINTER: &str = "version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 12345\n";
+
+    fn gate() -> LargeFiles {
+        LargeFiles {
+            enabled: true,
+            max_bytes: 200,
+            binary_allow: PathGlobs::new(&["*.png".to_string()]).unwrap(),
+            oversize: Some(Severity::Medium),
+            binary: Some(Severity::Low),
+            lfs: Some(Severity::High),
+        }
+    }
+
+    fn tags(gate: &LargeFiles, repo: &TestRepo, scope: ScopeMode) -> Vec<(String, String)> {
+        let mut ctx = Context::new(repo.path().to_path_buf(), scope);
+        // Ignored paths are still gated
+        ctx.ignore = PathGlobs::new(&["*.txt".to_string(), "*.bin".to_string()]).unwrap();
+        let mut found: Vec<(String, String)> = gate
+            .run(&ctx)
+            .unwrap()
+            .into_iter()
+            .map(|f| (f.location.unwrap().file, f.tags[0].clone()))
+            .collect();
+        found.sort();
+        found
+    }
+
+    fn repo() -> TestRepo {
+        let repo = TestRepo::new();
+        repo.write(
+            ".gitattributes",
+            "*.psd filter=lfs diff=lfs merge=lfs -text\n",
+        );
+        repo.write("big.txt", "x".repeat(201));
+        repo.write("small.txt", "x".repeat(200));
+        repo.write("tool.bin", b"\x7fELF\x00\x01");
+        repo.write("logo.png", b"\x89PNG\x00\x01");
+        repo.write("pointer.psd", POINTER);
+        repo.write("regular.psd", b"8BPS\x00\x01");
+        repo.git(&["add", "-A"]);
+        repo
+    }
+
+    #[test]
+    fn test_rules() {
+        let repo = repo();
+        let found = tags(&gate(), &repo, ScopeMode::Staged);
+        let expect = |v: &[(&str, &str)]| {
+            v.iter()
+                .map(|(p, t)| (p.to_string(), t.to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            found,
+            expect(&[
+                ("big.txt", "large-file"),
+                ("regular.psd", "binary"),
+                ("regular.psd", "lfs"),
+                ("tool.bin", "binary"),
+            ])
+        );
+
+        let off = |f: fn(&mut LargeFiles)| {
+            let mut gate = gate();
+            f(&mut gate);
+            tags(&gate, &repo, ScopeMode::Staged)
+        };
+        assert!(!off(|g| g.oversize = None)
+            .iter()
+            .any(|(_, t)| t == "large-file"));
+        assert!(!off(|g| g.binary = None).iter().any(|(_, t)| t == "binary"));
+        assert!(!off(|g| g.lfs = None).iter().any(|(_, t)| t == "lfs"));
+        assert!(off(|g| g.enabled = false).is_empty());
+    }
+
+    #[test]
+    fn test_smudged_lfs_file_in_worktree() {
+        let repo = repo();
+        repo.commit("init");
+        // What `git lfs pull` leaves on disk: the real content, with the pointer staged
+        repo.write("pointer.psd", b"8BPS\x00\x02");
+        repo.write("tool.bin", b"\x7fELF\x00\x02");
+        let found = tags(&gate(), &repo, ScopeMode::Worktree);
+        assert_eq!(found, vec![("tool.bin".to_string(), "binary".to_string())]);
+    }
+}
@@ -1 +1,8 @@
+pub mod benign;
+pub mod cargo_policy;
+pub mod commit_metadata;
 pub mod entropy_guard;
+pub mod forbidden_files;
+pub mod large_files;
+pub mod npm_lock;
+pub mod regex_rule;
@@ -0,0 +1,275 @@
+use anyhow::Result;
+use regex::Regex;
+
+use crate::checks::entropy_guard::{
+    lines_with_offsets, masked_snippet, shannon_entropy, token_location,
+};
+use crate::examples::Examples;
+use crate::glob::PathGlobs;
+use crate::targets::{get_target_files, TextFile};
+use crate::{Check, Finding, Severity};
+
+/// Capture group holding the secret part of a match, if the regex defines one.
+pub const SECRET_GROUP: &str = "secret";
+
+/// A user-defined rule (`[[rules]]` in `veto.toml`): a regex matched line by
+/// line against every target file whose path matches `paths`.
+///
+/// If the regex has a capture group named `secret`, the entropy gate,
+/// redaction and location apply to that group; otherwise to the whole match.
+pub struct RegexRule {
+    pub id: String,
+    pub title: String,
+    pub regex: Regex,
+    pub severity: Severity,
+    /// Path globs the rule applies to; empty means every file.
+    pub paths: PathGlobs,
+    /// Path globs the rule never applies to, even if they match `paths`.
+    pub exclude_paths: PathGlobs,
+    /// Minimum Shannon entropy of the secret for a match to count.
+    pub min_entropy: Option<f64>,
+    /// Regexes that suppress a match when they match its text.
+    pub allowlist: Vec<Regex>,
+    pub max_file_bytes: u64,
+    /// Longer lines are skipped (and recorded in coverage), as by `EntropyGuard`.
+    pub max_line_length: usize,
+    pub snippet_context: usize,
+    /// Sample lines; `paths` does not apply to them.
+    pub examples: Examples,
+}
+
+/// A match of a `RegexRule` within one line: byte range of the secret part.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RuleMatch {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Check for RegexRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn description(&self) -> &str {
+        &self.title
+    }
+
+    fn run(&self, ctx: &crate::Context) -> Result<Vec<Finding>> {
+        let mut findings = vec![];
+        for file in get_target_files(ctx, self.max_file_bytes)?.iter() {
+            if self.applies_to(&file.path) {
+                findings.extend(self.scan_content(ctx, file));
+            }
+        }
+        Ok(findings)
+    }
+
+    fn examples(&self) -> Examples {
+        self.examples.clone()
+    }
+
+    fn fires_on(&self, sample: &str) -> Option<bool> {
+        Some(sample.lines().any(|line| !self.matches(line).is_empty()))
+    }
+}
+
+impl RegexRule {
+    fn applies_to(&self, path: &str) -> bool {
+        (self.paths.is_empty() || self.paths.is_match(path)) && !self.exclude_paths.is_match(path)
+    }
+
+    /// Matches in one line that pass the allowlist and entropy gate.
+    pub fn matches(&self, line: &str) -> Vec<RuleMatch> {
+        let mut matches = vec![];
+        for caps in self.regex.captures_iter(line) {
+            let Some(whole) = caps.get(0) else {
+                continue;
+            };
+            if self.allowlist.iter().any(|a| a.is_match(whole.as_str())) {
+                continue;
+            }
+            let secret = caps.name(SECRET_GROUP).unwrap_or(whole);
+            // Nothing to report (e.g. an optional `secret` group that matched empty)
+            if secret.as_str().is_empty() {
+                continue;
+            }
+            if let Some(min) = self.min_entropy {
+                if shannon_entropy(secret.as_str()) < min {
+                    continue;
+                }
+            }
+            matches.push(RuleMatch {
+                start: secret.start(),
+                end: secret.end(),
+            });
+        }
+        matches
+    }
+
+    fn scan_content(&self, ctx: &crate::Context, file: &TextFile) -> Vec<Finding> {
+        let mut findings = vec![];
+        for (line_idx, (line_start, line)) in lines_with_offsets(&file.text).enumerate() {
+            if !file.includes_line(line_idx + 1) {
+                continue;
+            }
+            if line.len() > self.max_line_length {
+                ctx.coverage.line_skipped(&file.path, line_idx + 1);
+                continue;
+            }
+            for m in self.matches(line) {
+                let secret = &line[m.start..m.end];
+                findings.push(Finding {
+                    id: self.id.clone(),
+                    fingerprint: ctx.fingerprint(&self.id, &file.path, secret),
+                    title: self.title.clone(),
+                    message: format!(
+                        "Matches rule `{}`: {}",
+                        self.id,
+                        ctx.redaction.redact(secret)
+                    ),
+                    severity: self.severity,
+                    location: Some(token_location(
+                        &file.path,
+                        line_idx + 1,
+                        line_start,
+                        line,
+                        m.start,
+                        secret,
+                    )),
+                    tags: vec!["rule".to_string()],
+                    snippet: (self.snippet_context > 0)
+                        .then(|| masked_snippet(ctx, line, m.start, secret, self.snippet_context)),
+                    details: None,
+                });
+            }
+        }
+        findings
+    }
+}
+
+/// Whether `regex` can match an empty string, and so produce zero-length
+/// matches anywhere (`a*`, `\b`, `x?`).
+pub fn matches_empty(regex: &Regex) -> bool {
+    regex_syntax::parse(regex.as_str()).is_ok_and(|hir| hir.properties().minimum_len() == Some(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(regex: &str) -> RegexRule {
+        RegexRule {
+            id: "TEST-001".into(),
+            title: "test".into(),
+            regex: Regex::new(regex).unwrap(),
+            severity: Severity::Medium,
+            paths: PathGlobs::new(&[]).unwrap(),
+            exclude_paths: PathGlobs::new(&[]).unwrap(),
+            min_entropy: None,
+            allowlist: vec![],
+            max_file_bytes: 1024,
+            max_line_length: 1000,
+            snippet_context: 40,
+            examples: Examples::default(),
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let mut r = rule(r"token=(?P<secret>\w+)");
+        let line = "a token=aaaaaaaa b token=Zk8qP2vX9mL4";
+        assert_eq!(
+            r.matches(line),
+            vec![
+                RuleMatch { start: 8, end: 16 },
+                RuleMatch { start: 25, end: 37 }
+            ]
+        );
+
+        // The entropy gate applies to the `secret` group
+        r.min_entropy = Some(3.0);
+        assert_eq!(r.matches(line), vec![RuleMatch { start: 25, end: 37 }]);
+
+        // The allowlist applies to the whole match
+        r.allowlist = vec![Regex::new("^token=Zk8q").unwrap()];
+        assert!(r.matches(line).is_empty());
+    }
+
+    #[test]
+    fn test_paths() {
+        let mut r = rule("x");
+        assert!(r.applies_to("any/file.txt"));
+        r.paths = PathGlobs::new(&["deploy/**".into(), "*.env".into()]).unwrap();
+        r.exclude_paths = PathGlobs::new(&["deploy/vendor/**".into(), "test.env".into()]).unwrap();
+        assert!(r.applies_to("deploy/k8s/app.yaml"));
+        assert!(r.applies_to("svc/prod.env"));
+        assert!(!r.applies_to("src/deploy/app.yaml"));
+        assert!(!r.applies_to("deploy/vendor/lib.yaml"));
+        assert!(!r.applies_to("svc/test.env"));
+    }
+
+    #[test]
+    fn test_finding_location() {
+        use crate::runner::{ScopeMode, SharedBytes};
+        use crate::Context;
+
+        let r = rule(r"(?:ip|host) = (?P<secret>10\.\d+\.\d+\.\d+)");
+        let ctx = Context::new(
+            Default::default(),
+            ScopeMode::Stdin {
+                name: "deploy/app.env".into(),
+                content: SharedBytes::from("name = \"é\"\n  host = 10.1.2.3\n".as_bytes().to_vec()),
+            },
+        );
+        let findings = r.run(&ctx).unwrap();
+        assert_eq!(findings.len(), 1);
+        let loc = findings[0].location.as_ref().unwrap();
+        assert_eq!(loc.file, "deploy/app.env");
+        assert_eq!(
+            (loc.line, loc.column, loc.end_column),
+            (Some(2), Some(10), Some(18))
+        );
+        // "name = "é"\n" is 12 bytes
+        assert_eq!((loc.start_byte, loc.end_byte), (Some(21), Some(29)));
+    }
+
+    #[test]
+    fn test_long_lines_and_shared_files() {
+        use crate::runner::ScopeMode;
+        use crate::Context;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.env");
+        let long = format!("host = 10.0.0.1 {}", "x".repeat(990));
+        std::fs::write(&path, format!("{}\nhost = 10.0.0.2\n", long)).unwrap();
+        let ctx = Context::new(Default::default(), ScopeMode::Paths(vec![path.clone()]));
+        let hosts = rule(r"host = (?P<secret>10\.\d+\.\d+\.\d+)");
+        let ips = rule(r"(?P<secret>10\.\d+\.\d+\.\d+)");
+
+        // The line over `max_line_length` is skipped, and counted once for both rules
+        let lines = |r: &RegexRule| -> Vec<Option<u32>> {
+            r.run(&ctx)
+                .unwrap()
+                .into_iter()
+                .map(|f| f.location.unwrap().line)
+                .collect()
+        };
+        assert_eq!(lines(&hosts), vec![Some(2)]);
+        // Files are loaded once per scan: the second rule does not read them again
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(lines(&ips), vec![Some(2)]);
+        assert_eq!(ctx.coverage.take().lines_skipped(), 1);
+    }
+
+    #[test]
+    fn test_matches_empty() {
+        for pattern in ["a*", r"\b", "x?", "^", r"(?P<secret>\w*)"] {
+            assert!(matches_empty(&Regex::new(pattern).unwrap()), "{}", pattern);
+        }
+        for pattern in ["a+", r"\bkey\b", r"token=(?P<secret>\w*)"] {
+            assert!(!matches_empty(&Regex::new(pattern).unwrap()), "{}", pattern);
+        }
+        // A non-empty match with an empty `secret` group is not reported
+        assert!(rule(r"token=(?P<secret>\w*)").matches("token=").is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+use crate::model::{
+    Coverage, DecodedFile, DroppedTokens, Encoding, SkipReason, SkippedFile, TruncatedFile,
+};
+
+/// Collects scan coverage from checks while they run.
+///
+/// Shared through `Context`, so every check that loads file content reports
+/// into the same place; the `Runner` turns it into `Report::coverage`.
+#[derive(Debug, Clone, Default)]
+pub struct CoverageRecorder {
+    inner: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    scanned: BTreeSet<String>,
+    skipped: BTreeSet<SkippedFile>,
+    truncated: BTreeMap<String, TruncatedFile>,
+    decoded: BTreeMap<String, Encoding>,
+    dropped: BTreeMap<(String, String), usize>,
+    /// Line numbers skipped per file, so checks skipping the same line count it once.
+    long_lines: BTreeMap<String, BTreeSet<usize>>,
+}
+
+impl CoverageRecorder {
+    pub fn scanned(&self, file: &str) {
+        self.with(|s| {
+            s.scanned.insert(file.to_string());
+        });
+    }
+
+    /// Records the encoding of a scanned file; plain UTF-8 is the default and not listed.
+    pub fn decoded(&self, file: &str, encoding: Encoding) {
+        if encoding == Encoding::Utf8 {
+            return;
+        }
+        self.with(|s| {
+            s.decoded.insert(file.to_string(), encoding);
+        });
+    }
+
+    pub fn skipped(&self, file: &str, reason: SkipReason) {
+        self.with(|s| {
+            s.skipped.insert(SkippedFile {
+                file: file.to_string(),
+                reason,
+            });
+        });
+    }
+
+    /// Records that line `line` (1-based) of `file` exceeded `max_line_length`.
+    pub fn line_skipped(&self, file: &str, line: usize) {
+        self.with(|s| {
+            let lines = s.long_lines.entry(file.to_string()).or_default();
+            lines.insert(line);
+            let count = lines.len();
+            s.truncated_entry(file).lines_skipped = count;
+        });
+    }
+
+    pub fn token_limit_reached(&self, file: &str) {
+        self.with(|s| s.truncated_entry(file).token_limit_reached = true);
+    }
+
+    /// Records a token `check` did not report because `classifier` recognised it as benign.
+    pub fn token_dropped(&self, check: &str, classifier: &str) {
+        self.with(|s| {
+            *s.dropped
+                .entry((check.to_string(), classifier.to_string()))
+                .or_default() += 1;
+        });
+    }
+
+    /// Drains everything recorded so far. Check timings are filled in by the `Runner`.
+    pub fn take(&self) -> Coverage {
+        let state = self.with(std::mem::take);
+        Coverage {
+            files_scanned: state.scanned.len(),
+            files_skipped: state.skipped.into_iter().collect(),
+            files_truncated: state.truncated.into_values().collect(),
+            files_decoded: state
+                .decoded
+                .into_iter()
+                .map(|(file, encoding)| DecodedFile { file, encoding })
+                .collect(),
+            tokens_dropped: state
+                .dropped
+                .into_iter()
+                .map(|((check, classifier), count)| DroppedTokens {
+                    check,
+                    classifier,
+                    count,
+                })
+                .collect(),
+            checks: vec![],
+        }
+    }
+
+    fn with<T>(&self, f: impl FnOnce(&mut State) -> T) -> T {
+        let mut state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut state)
+    }
+}
+
+impl State {
+    fn truncated_entry(&mut self, file: &str) -> &mut TruncatedFile {
+        self.truncated
+            .entry(file.to_string())
+            .or_insert_with(|| TruncatedFile {
+                file: file.to_string(),
+                ..Default::default()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_dedupes_and_drains() {
+        let rec = CoverageRecorder::default();
+        rec.scanned("a.txt");
+        rec.scanned("a.txt");
+        rec.skipped("b.bin", SkipReason::Binary);
+        rec.skipped("b.bin", SkipReason::Binary);
+        rec.line_skipped("a.txt", 4);
+        rec.line_skipped("a.txt", 9);
+        // e.g. by another check
+        rec.line_skipped("a.txt", 4);
+        rec.line_skipped("a.txt", 12);
+        rec.token_limit_reached("a.txt");
+
+        let cov = rec.take();
+        assert_eq!(cov.files_scanned, 1);
+        assert_eq!(cov.files_skipped.len(), 1);
+        assert_eq!(cov.lines_skipped(), 3);
+        assert!(cov.files_truncated[0].token_limit_reached);
+
+        let empty = rec.take();
+        assert_eq!(empty.files_scanned, 0);
+        assert!(empty.files_skipped.is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+use crate::model::Encoding;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+const SNIFF_BYTES: usize = 1024;
+
+/// Decodes file content to text, detecting the encoding.
+///
+/// Order: BOM, BOM-less UTF-16 (NUL-interleaved ASCII), binary, UTF-8, then
+/// ISO-8859-1 as a fallback that never fails (every byte maps to a char).
+/// Returns `None` for binary content.
+pub fn decode(data: &[u8]) -> Option<(String, Encoding)> {
+    if let Some(rest) = data.strip_prefix(UTF8_BOM) {
+        return Some((
+            String::from_utf8_lossy(rest).into_owned(),
+            Encoding::Utf8Bom,
+        ));
+    }
+    if let Some(rest) = data.strip_prefix(UTF16LE_BOM) {
+        return Some((decode_utf16(rest, u16::from_le_bytes), Encoding::Utf16Le));
+    }
+    if let Some(rest) = data.strip_prefix(UTF16BE_BOM) {
+        return Some((decode_utf16(rest, u16::from_be_bytes), Encoding::Utf16Be));
+    }
+    if let Some(encoding) = sniff_utf16(data) {
+        let text = match encoding {
+            Encoding::Utf16Be => decode_utf16(data, u16::from_be_bytes),
+            _ => decode_utf16(data, u16::from_le_bytes),
+        };
+        return Some((text, encoding));
+    }
+
+    if has_nul(data) {
+        return None;
+    }
+    match std::str::from_utf8(data) {
+        Ok(s) => Some((s.to_string(), Encoding::Utf8)),
+        Err(_) => Some((data.iter().map(|&b| b as char).collect(), Encoding::Latin1)),
+    }
+}
+
+/// Whether `decode` would treat the content as binary.
+pub fn is_binary(data: &[u8]) -> bool {
+    let utf16 = data.starts_with(UTF16LE_BOM)
+        || data.starts_with(UTF16BE_BOM)
+        || sniff_utf16(data).is_some();
+    !utf16 && has_nul(data)
+}
+
+// Simple heuristic for binary content
+fn has_nul(data: &[u8]) -> bool {
+    // Check first 1024 bytes for null byte
+    data.iter().take(SNIFF_BYTES).any(|&b| b == 0)
+}
+
+/// BOM-less UTF-16: mostly-ASCII text has a NUL in every other byte.
+fn sniff_utf16(data: &[u8]) -> Option<Encoding> {
+    let head = &data[..data.len().min(SNIFF_BYTES)];
+    let pairs = head.len() / 2;
+    if pairs < 2 {
+        return None;
+    }
+    let mut even_nuls = 0;
+    let mut odd_nuls = 0;
+    for pair in head.chunks_exact(2) {
+        even_nuls += usize::from(pair[0] == 0);
+        odd_nuls += usize::from(pair[1] == 0);
+    }
+    // Require a clear majority on one side and (almost) none on the other.
+    if odd_nuls * 10 >= pairs * 9 && even_nuls * 100 <= pairs {
+        Some(Encoding::Utf16Le)
+    } else if even_nuls * 10 >= pairs * 9 && odd_nuls * 100 <= pairs {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+fn decode_utf16(data: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(s: &str, bom: bool) -> Vec<u8> {
+        let mut out = if bom { UTF16LE_BOM.to_vec() } else { vec![] };
+        for unit in s.encode_utf16() {
+            out.extend_from_slice(&unit.to_le_bytes());
+        }
+        out
+    }
+
+    #[test]
+    fn test_decode_utf16() {
+        let text = "$token = \"7Fz2X9kL1mN4pQ3r\"\r\n";
+        assert_eq!(
+            decode(&utf16le(text, true)),
+            Some((text.to_string(), Encoding::Utf16Le))
+        );
+        assert_eq!(
+            decode(&utf16le(text, false)),
+            Some((text.to_string(), Encoding::Utf16Le))
+        );
+
+        let mut be = UTF16BE_BOM.to_vec();
+        for unit in text.encode_utf16() {
+            be.extend_from_slice(&unit.to_be_bytes());
+        }
+        assert_eq!(decode(&be), Some((text.to_string(), Encoding::Utf16Be)));
+    }
+
+    #[test]
+    fn test_decode_utf8_and_fallback() {
+        assert_eq!(
+            decode("héllo".as_bytes()),
+            Some(("héllo".to_string(), Encoding::Utf8))
+        );
+        assert_eq!(
+            decode(b"\xEF\xBB\xBFkey=1"),
+            Some(("key=1".to_string(), Encoding::Utf8Bom))
+        );
+        // Latin-1 "café"
+        assert_eq!(
+            decode(b"caf\xe9"),
+            Some(("café".to_string(), Encoding::Latin1))
+        );
+        assert_eq!(decode(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), None);
+    }
+
+    #[test]
+    fn test_encoding_names_match_json() {
+        for encoding in [
+            Encoding::Utf8,
+            Encoding::Utf8Bom,
+            Encoding::Utf16Le,
+            Encoding::Utf16Be,
+            Encoding::Latin1,
+        ] {
+            assert_eq!(serde_json::to_value(encoding).unwrap(), encoding.as_str());
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::Check;
+
+/// Samples a check must (`should_match`) and must not (`should_not_match`) fire
+/// on, run by `veto rules test`.
+///
+/// A sample is text for content checks and a path for path checks.
+#[derive(Debug, Clone, Default)]
+pub struct Examples {
+    pub should_match: Vec<String>,
+    pub should_not_match: Vec<String>,
+}
+
+impl Examples {
+    pub fn new(should_match: &[&str], should_not_match: &[&str]) -> Self {
+        let to_vec = |v: &[&str]| v.iter().map(|s| s.to_string()).collect();
+        Self {
+            should_match: to_vec(should_match),
+            should_not_match: to_vec(should_not_match),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.should_match.len() + self.should_not_match.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// These examples followed by `other`'s.
+    pub fn chain(mut self, other: &Examples) -> Self {
+        self.should_match.extend(other.should_match.iter().cloned());
+        self.should_not_match
+            .extend(other.should_not_match.iter().cloned());
+        self
+    }
+}
+
+/// An example whose outcome differs from the expectation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExampleFailure {
+    /// `true` for a `should_match` sample the check did not fire on.
+    pub should_match: bool,
+    pub sample: String,
+}
+
+/// Result of running one check's examples.
+#[derive(Debug, Clone)]
+pub struct ExampleReport {
+    pub check_id: String,
+    pub total: usize,
+    pub failures: Vec<ExampleFailure>,
+}
+
+/// Runs `check`'s examples; `None` if it has none or cannot evaluate samples.
+pub fn test_examples(check: &dyn Check) -> Option<ExampleReport> {
+    let examples = check.examples();
+    if examples.is_empty() {
+        return None;
+    }
+    let mut failures = vec![];
+    for (samples, should_match) in [
+        (&examples.should_match, true),
+        (&examples.should_not_match, false),
+    ] {
+        for sample in samples {
+            if check.fires_on(sample)? != should_match {
+                failures.push(ExampleFailure {
+                    should_match,
+                    sample: sample.clone(),
+                });
+            }
+        }
+    }
+    Some(ExampleReport {
+        check_id: check.id().to_string(),
+        total: examples.len(),
+        failures,
+    })
+}
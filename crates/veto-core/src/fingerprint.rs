@@ -0,0 +1,63 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Key used when none is configured. Fingerprints stay stable across machines,
+/// but only a private key prevents guessing short secrets from a fingerprint.
+pub const DEFAULT_KEY: &[u8] = b"veto-fingerprint-v1";
+
+/// Stable identity of a finding: HMAC-SHA256 over check id, normalised path and
+/// matched content, truncated to 128 bits. Line numbers are deliberately left
+/// out so the fingerprint survives code moving around.
+pub fn fingerprint(key: &[u8], check_id: &str, path: &str, content: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for part in [check_id, &normalize_path(path), content] {
+        mac.update(&(part.len() as u64).to_be_bytes());
+        mac.update(part.as_bytes());
+    }
+    mac.finalize().into_bytes()[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Forward slashes, no leading `./`, no duplicate separators.
+pub fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_is_stable_and_keyed() {
+        let a = fingerprint(DEFAULT_KEY, "EG-001", "src/app.rs", "token");
+        assert_eq!(a.len(), 32);
+        assert_eq!(
+            a,
+            fingerprint(DEFAULT_KEY, "EG-001", "./src//app.rs", "token")
+        );
+        assert_eq!(
+            a,
+            fingerprint(DEFAULT_KEY, "EG-001", "src\\app.rs", "token")
+        );
+        assert_ne!(
+            a,
+            fingerprint(DEFAULT_KEY, "EG-001", "src/app.rs", "token2")
+        );
+        assert_ne!(a, fingerprint(DEFAULT_KEY, "FF-001", "src/app.rs", "token"));
+        assert_ne!(
+            a,
+            fingerprint(b"other-key", "EG-001", "src/app.rs", "token")
+        );
+        // Field boundaries are unambiguous
+        assert_ne!(
+            fingerprint(DEFAULT_KEY, "EG-001", "a", "bc"),
+            fingerprint(DEFAULT_KEY, "EG-001", "ab", "c")
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use anyhow::{Context as _, Result};
+use globset::{Glob, GlobBuilder, GlobMatcher};
+
+/// A list of path globs, matched against repo-relative paths.
+///
+/// Patterns without a `/` match the file name at any depth (`*.pem`, `.env`),
+/// patterns containing a `/` match the whole path (`deploy/**/*.tfstate`).
+#[derive(Debug, Clone, Default)]
+pub struct PathGlobs {
+    globs: Vec<(String, GlobMatcher, bool)>,
+}
+
+impl PathGlobs {
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let mut globs = Vec::with_capacity(patterns.len());
+        for pattern in patterns {
+            let full_path = pattern.contains('/');
+            let glob = if full_path {
+                GlobBuilder::new(pattern.trim_start_matches('/'))
+                    .literal_separator(true)
+                    .build()
+            } else {
+                Glob::new(pattern)
+            }
+            .with_context(|| format!("invalid glob pattern: {:?}", pattern))?;
+            globs.push((pattern.clone(), glob.compile_matcher(), full_path));
+        }
+        Ok(Self { globs })
+    }
+
+    /// Returns the first pattern matching `path`, if any.
+    pub fn matched(&self, path: &str) -> Option<&str> {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        self.globs
+            .iter()
+            .find(|(_, m, full_path)| {
+                if *full_path {
+                    m.is_match(path)
+                } else {
+                    m.is_match(file_name)
+                }
+            })
+            .map(|(p, _, _)| p.as_str())
+    }
+
+    pub fn is_match(&self, path: &str) -> bool {
+        self.matched(path).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.globs.is_empty()
+    }
+}
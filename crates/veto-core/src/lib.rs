@@ -1,6 +1,18 @@
+pub mod calibrate;
 pub mod checks;
+pub mod coverage;
+pub mod encoding;
+pub mod examples;
+pub mod fingerprint;
+pub mod glob;
 pub mod model;
+pub mod osv;
+pub mod redact;
 pub mod runner;
+pub mod schema;
+pub mod targets;
+#[cfg(any(test, feature = "testutil"))]
+pub mod testutil;
 
 pub use model::{Finding, Report, Severity};
 pub use runner::{Check, Context, Runner};
@@ -1,6 +1,9 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
 pub enum Severity {
     Low,
     Medium,
@@ -8,35 +11,212 @@ pub enum Severity {
     Critical,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Where a finding is. Lines and columns are 1-based; columns count characters,
+/// and `end_column` points just past the match. Byte offsets are 0-based
+/// (`start_byte..end_byte`) into the decoded file text.
+///
+/// Findings about a commit rather than a file (e.g. its message) set `commit`
+/// and use a synthetic `file` such as `<commit message>`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default, PartialEq)]
 pub struct Location {
     pub file: String,
     pub line: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_byte: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_byte: Option<u64>,
+    /// Commit the finding is about, for commit metadata (message, author).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+impl Location {
+    /// A whole-file location.
+    pub fn file(file: impl Into<String>) -> Self {
+        Self {
+            file: file.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether `file` is a real path, rather than a synthetic one such as
+    /// `<commit message>`.
+    pub fn is_file(&self) -> bool {
+        !(self.commit.is_some() && self.file.starts_with('<') && self.file.ends_with('>'))
+    }
+
+    /// `file`, prefixed with the abbreviated commit (`<rev>:<file>`, as in `git show`) if set.
+    pub fn subject(&self) -> String {
+        match &self.commit {
+            Some(commit) => format!("{}:{}", &commit[..commit.len().min(12)], self.file),
+            None => self.file.clone(),
+        }
+    }
+
+    /// `file:line[:column]`, as understood by editors and terminals.
+    pub fn display(&self) -> String {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{}:{}:{}", self.subject(), line, column),
+            (Some(line), None) => format!("{}:{}", self.subject(), line),
+            _ => self.subject(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct FindingDetails {
+    /// Shannon entropy of the token (bits/char)
     pub entropy: f64,
+    /// `entropy` relative to the maximum for the token's charset and length (0..1)
+    #[serde(default)]
+    pub normalized_entropy: f64,
     pub token_len: usize,
     pub charset: String,
+    /// Known-benign classifier that matched a demoted finding (e.g. `uuid`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classifier: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Finding {
     pub id: String,
+    /// Stable per-finding identity (see `fingerprint::fingerprint`); unlike `id`,
+    /// unique to this finding and unchanged when lines shift.
+    #[serde(default)]
+    pub fingerprint: String,
     pub title: String,
     pub message: String,
     pub severity: Severity,
     pub location: Option<Location>,
     pub tags: Vec<String>,
+    /// The surrounding line with the match (and anything else secret-looking) masked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<FindingDetails>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Why a file was not scanned (or not scanned completely).
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    TooLarge,
+    Binary,
+    Unreadable,
+}
+
+impl SkipReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkipReason::TooLarge => "too large",
+            SkipReason::Binary => "binary",
+            SkipReason::Unreadable => "unreadable",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SkippedFile {
+    pub file: String,
+    pub reason: SkipReason,
+}
+
+/// Text encoding a file was decoded from.
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub enum Encoding {
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "utf-8-bom")]
+    Utf8Bom,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    /// Fallback for content that is not valid UTF-8.
+    #[serde(rename = "latin1")]
+    Latin1,
+}
+
+impl Encoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf-8",
+            Encoding::Utf8Bom => "utf-8-bom",
+            Encoding::Utf16Le => "utf-16le",
+            Encoding::Utf16Be => "utf-16be",
+            Encoding::Latin1 => "latin1",
+        }
+    }
+}
+
+/// A file decoded from something other than plain UTF-8.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct DecodedFile {
+    pub file: String,
+    pub encoding: Encoding,
+}
+
+/// A file that was scanned, but not completely.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+pub struct TruncatedFile {
+    pub file: String,
+    /// Lines skipped because they exceeded `max_line_length`.
+    pub lines_skipped: usize,
+    /// Whether scanning stopped early at `max_tokens_per_file`.
+    pub token_limit_reached: bool,
+}
+
+/// Tokens a check dropped as known-benign (see `checks::benign`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct DroppedTokens {
+    pub check: String,
+    pub classifier: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CheckTiming {
+    pub id: String,
+    #[serde(default)]
+    pub description: String,
+    pub duration_ms: u128,
+    pub findings: usize,
+}
+
+/// What a scan actually looked at, so a partial scan doesn't look like a clean one.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct Coverage {
+    pub files_scanned: usize,
+    pub files_skipped: Vec<SkippedFile>,
+    pub files_truncated: Vec<TruncatedFile>,
+    pub files_decoded: Vec<DecodedFile>,
+    #[serde(default)]
+    pub tokens_dropped: Vec<DroppedTokens>,
+    pub checks: Vec<CheckTiming>,
+}
+
+impl Coverage {
+    pub fn lines_skipped(&self) -> usize {
+        self.files_truncated.iter().map(|t| t.lines_skipped).sum()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Report {
     pub findings: Vec<Finding>,
     pub duration_ms: u128,
+    #[serde(default)]
+    pub coverage: Coverage,
 }
 
 impl Report {
@@ -44,3 +224,67 @@ impl Report {
         self.findings.iter().map(|f| f.severity).max()
     }
 }
+
+/// Version of the JSON report format (`ReportEnvelope`). Bump on any breaking
+/// change to the serialized shape of the types in this module.
+pub const REPORT_SCHEMA_VERSION: &str = "1";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolInfo {
+    pub name: String,
+    pub version: String,
+}
+
+/// Describes a scan independently of its results (also the NDJSON header).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScanMetadata {
+    pub tool: ToolInfo,
+    pub repo_root: String,
+    /// Commit scanned: `HEAD` at scan time, or the newest commit of a diff/commits scope.
+    pub commit: Option<String>,
+    pub scope: String,
+    /// SHA-256 of the effective configuration; secret values (fingerprint key,
+    /// redaction salt) are not part of it.
+    pub config_hash: Option<String>,
+    /// RFC 3339 timestamp.
+    pub generated_at: String,
+    /// Ids of the checks that were run.
+    pub checks: Vec<String>,
+}
+
+/// The versioned JSON report: `schema_version`, scan metadata, then the report itself.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReportEnvelope {
+    pub schema_version: String,
+    #[serde(flatten)]
+    pub metadata: ScanMetadata,
+    #[serde(flatten)]
+    pub report: Report,
+}
+
+impl ReportEnvelope {
+    pub fn new(metadata: ScanMetadata, report: Report) -> Self {
+        Self {
+            schema_version: REPORT_SCHEMA_VERSION.to_string(),
+            metadata,
+            report,
+        }
+    }
+}
+
+/// One line of the NDJSON report: a header, then findings as they are produced,
+/// then a summary.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamRecord {
+    Header {
+        schema_version: String,
+        #[serde(flatten)]
+        metadata: ScanMetadata,
+    },
+    Finding(Finding),
+    Summary {
+        duration_ms: u128,
+        coverage: Coverage,
+    },
+}
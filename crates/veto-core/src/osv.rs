@@ -0,0 +1,337 @@
+//! A local copy of the OSV advisory database (https://ossf.github.io/osv-schema/):
+//! a directory of OSV JSON files, such as an extracted `npm/all.zip` from
+//! osv.dev. Nothing is fetched over the network.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use serde::Deserialize;
+
+/// An advisory, without its affected ranges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Advisory {
+    pub id: String,
+    pub summary: String,
+    pub aliases: Vec<String>,
+    /// `database_specific.severity` if set (GitHub advisories: "LOW",
+    /// "MODERATE", "HIGH", "CRITICAL").
+    pub severity: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct OsvDb {
+    advisories: Vec<Advisory>,
+    /// (ecosystem, package name) -> (index into `advisories`, what it affects)
+    affected: HashMap<(String, String), Vec<(usize, Affected)>>,
+}
+
+#[derive(Debug)]
+struct Affected {
+    versions: Vec<String>,
+    ranges: Vec<Vec<Event>>,
+}
+
+#[derive(Debug)]
+enum Event {
+    Introduced(Version),
+    Fixed(Version),
+    LastAffected(Version),
+}
+
+impl Event {
+    fn version(&self) -> &Version {
+        match self {
+            Event::Introduced(v) | Event::Fixed(v) | Event::LastAffected(v) => v,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RawAdvisory {
+    id: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    withdrawn: Option<String>,
+    #[serde(default)]
+    affected: Vec<RawAffected>,
+    #[serde(default)]
+    database_specific: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct RawAffected {
+    package: Option<RawPackage>,
+    #[serde(default)]
+    versions: Vec<String>,
+    #[serde(default)]
+    ranges: Vec<RawRange>,
+}
+
+#[derive(Deserialize)]
+struct RawPackage {
+    ecosystem: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct RawRange {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<HashMap<String, String>>,
+}
+
+impl OsvDb {
+    /// Loads every `*.json` file under `dir`, recursively.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut db = Self::default();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let entries = fs::read_dir(&dir)
+                .with_context(|| format!("cannot read OSV database {}", dir.display()))?;
+            for entry in entries {
+                let path = entry?.path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else if path.extension().is_some_and(|e| e == "json") {
+                    let json = fs::read_to_string(&path)
+                        .with_context(|| format!("cannot read {}", path.display()))?;
+                    db.add(&json)
+                        .with_context(|| format!("invalid OSV advisory {}", path.display()))?;
+                }
+            }
+        }
+        Ok(db)
+    }
+
+    /// Adds one OSV advisory. Withdrawn advisories are ignored.
+    pub fn add(&mut self, json: &str) -> Result<()> {
+        let raw: RawAdvisory = serde_json::from_str(json)?;
+        if raw.withdrawn.is_some() {
+            return Ok(());
+        }
+        let index = self.advisories.len();
+        for affected in raw.affected {
+            let Some(package) = affected.package else {
+                continue;
+            };
+            let ranges = affected
+                .ranges
+                .into_iter()
+                .filter(|r| r.kind == "SEMVER" || r.kind == "ECOSYSTEM")
+                .map(|r| r.events.iter().filter_map(parse_event).collect())
+                .collect();
+            self.affected
+                .entry((package.ecosystem, package.name))
+                .or_default()
+                .push((
+                    index,
+                    Affected {
+                        versions: affected.versions,
+                        ranges,
+                    },
+                ));
+        }
+        let severity = raw
+            .database_specific
+            .as_ref()
+            .and_then(|d| d.get("severity"))
+            .and_then(|s| s.as_str())
+            .map(str::to_string);
+        self.advisories.push(Advisory {
+            id: raw.id,
+            summary: raw.summary,
+            aliases: raw.aliases,
+            severity,
+        });
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.advisories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+
+    /// Advisories affecting `version` of package `name` in `ecosystem` (e.g. "npm").
+    pub fn affecting(&self, ecosystem: &str, name: &str, version: &str) -> Vec<&Advisory> {
+        let Some(entries) = self
+            .affected
+            .get(&(ecosystem.to_string(), name.to_string()))
+        else {
+            return vec![];
+        };
+        let parsed = Version::parse(version);
+        let mut found: Vec<usize> = entries
+            .iter()
+            .filter(|(_, a)| {
+                a.versions.iter().any(|v| v == version)
+                    || parsed
+                        .as_ref()
+                        .is_some_and(|v| a.ranges.iter().any(|r| in_range(r, v)))
+            })
+            .map(|(i, _)| *i)
+            .collect();
+        found.dedup();
+        found.into_iter().map(|i| &self.advisories[i]).collect()
+    }
+}
+
+fn parse_event(event: &HashMap<String, String>) -> Option<Event> {
+    let (kind, version) = event.iter().next()?;
+    let version = Version::parse(version)?;
+    match kind.as_str() {
+        "introduced" => Some(Event::Introduced(version)),
+        "fixed" => Some(Event::Fixed(version)),
+        "last_affected" => Some(Event::LastAffected(version)),
+        _ => None,
+    }
+}
+
+/// Whether `version` is affected by a range, per the OSV evaluation rules:
+/// events in version order, each `introduced` at or below `version` opening
+/// the range and each later `fixed` / `last_affected` closing it.
+fn in_range(events: &[Event], version: &Version) -> bool {
+    let mut events: Vec<&Event> = events.iter().collect();
+    events.sort_by(|a, b| a.version().cmp(b.version()));
+    let mut affected = false;
+    for event in events {
+        match event {
+            Event::Introduced(v) if v <= version => affected = true,
+            Event::Fixed(v) if v <= version => affected = false,
+            Event::LastAffected(v) if v < version => affected = false,
+            _ => {}
+        }
+    }
+    affected
+}
+
+/// A semantic version (`1.2.3-beta.1`), compared by SemVer precedence. Build
+/// metadata is dropped; missing minor/patch parts count as 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    core: [u64; 3],
+    pre: Vec<String>,
+}
+
+impl Version {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().trim_start_matches('v');
+        let s = s.split('+').next()?;
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) => (core, pre.split('.').map(str::to_string).collect()),
+            None => (s, vec![]),
+        };
+        let mut parts = core.split('.');
+        let mut numbers = [0; 3];
+        for (i, part) in parts.by_ref().take(3).enumerate() {
+            numbers[i] = part.parse().ok()?;
+        }
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self { core: numbers, pre })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.core.cmp(&other.core).then_with(|| {
+            match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                // A pre-release is lower than its release
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => {
+                    for (a, b) in self.pre.iter().zip(&other.pre) {
+                        let order = match (a.parse::<u64>(), b.parse::<u64>()) {
+                            (Ok(a), Ok(b)) => a.cmp(&b),
+                            (Ok(_), Err(_)) => Ordering::Less,
+                            (Err(_), Ok(_)) => Ordering::Greater,
+                            (Err(_), Err(_)) => a.cmp(b),
+                        };
+                        if order != Ordering::Equal {
+                            return order;
+                        }
+                    }
+                    self.pre.len().cmp(&other.pre.len())
+                }
+            }
+        })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_affecting() {
+        let mut db = OsvDb::default();
+        db.add(
+            r#"{
+                "id": "GHSA-aaaa-bbbb-cccc",
+                "summary": "Prototype pollution",
+                "aliases": ["CVE-2020-8203"],
+                "affected": [{
+                    "package": {"ecosystem": "npm", "name": "lodash"},
+                    "ranges": [{"type": "SEMVER", "events": [
+                        {"introduced": "0"}, {"fixed": "4.17.19"},
+                        {"introduced": "5.0.0-beta.1"}, {"last_affected": "5.0.0"}
+                    ]}]
+                }],
+                "database_specific": {"severity": "HIGH"}
+            }"#,
+        )
+        .unwrap();
+        db.add(r#"{"id": "OLD-1", "withdrawn": "2021-01-01T00:00:00Z", "affected": []}"#)
+            .unwrap();
+        assert_eq!(db.len(), 1);
+
+        let ids = |version| -> Vec<String> {
+            db.affecting("npm", "lodash", version)
+                .iter()
+                .map(|a| a.id.clone())
+                .collect()
+        };
+        assert_eq!(ids("4.17.15"), vec!["GHSA-aaaa-bbbb-cccc"]);
+        assert!(ids("4.17.19").is_empty());
+        assert!(ids("4.17.21").is_empty());
+        assert_eq!(ids("5.0.0-beta.2").len(), 1);
+        assert_eq!(ids("5.0.0").len(), 1);
+        assert!(ids("5.0.1").is_empty());
+        assert!(db.affecting("crates.io", "lodash", "1.0.0").is_empty());
+        assert_eq!(
+            db.affecting("npm", "lodash", "1.0.0")[0]
+                .severity
+                .as_deref(),
+            Some("HIGH")
+        );
+    }
+
+    #[test]
+    fn test_version_order() {
+        let v = |s| Version::parse(s).unwrap();
+        assert!(v("1.0.0-alpha") < v("1.0.0-alpha.1"));
+        assert!(v("1.0.0-alpha.1") < v("1.0.0-alpha.beta"));
+        assert!(v("1.0.0-beta.2") < v("1.0.0-beta.11"));
+        assert!(v("1.0.0-rc.1") < v("1.0.0"));
+        assert!(v("1.9.0") < v("1.10.0"));
+        assert_eq!(v("v2.1.0+build.5"), v("2.1"));
+        assert_eq!(Version::parse("1.2.3.4"), None);
+    }
+}
@@ -0,0 +1,71 @@
+use sha2::{Digest, Sha256};
+
+/// How matched secrets are rendered anywhere they leave a check
+/// (messages, snippets, and therefore every output format).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Redaction {
+    /// First and last 4 characters (`abcd...wxyz`); `***` for 8 characters or fewer.
+    #[default]
+    Partial,
+    /// Nothing revealed.
+    Full,
+    /// Salted SHA-256 prefix, for correlating the same secret across reports.
+    Hash { salt: String },
+    /// Only the length.
+    Length,
+}
+
+impl Redaction {
+    pub fn redact(&self, token: &str) -> String {
+        match self {
+            Redaction::Partial => mask_token(token),
+            Redaction::Full => "[REDACTED]".to_string(),
+            Redaction::Hash { salt } => {
+                let mut hasher = Sha256::new();
+                hasher.update(salt.as_bytes());
+                hasher.update(token.as_bytes());
+                let digest: String = hasher.finalize()[..6]
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect();
+                format!("[sha256:{}]", digest)
+            }
+            Redaction::Length => format!("[REDACTED len={}]", token.chars().count()),
+        }
+    }
+}
+
+fn mask_token(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() <= 8 {
+        return "***".to_string();
+    }
+    let start: String = chars[..4].iter().collect();
+    let end: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}...{}", start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        assert_eq!(mask_token("secret"), "***");
+        assert_eq!(mask_token("1234567890"), "1234...7890");
+    }
+
+    #[test]
+    fn test_modes() {
+        let token = "Zk9xQ2bR7vLp3TnW8yHs4JdK6mXa";
+        assert_eq!(Redaction::Partial.redact(token), "Zk9x...6mXa");
+        assert_eq!(Redaction::Full.redact(token), "[REDACTED]");
+        assert_eq!(Redaction::Length.redact(token), "[REDACTED len=28]");
+
+        let salted = |salt: &str| Redaction::Hash { salt: salt.into() }.redact(token);
+        assert_eq!(salted("a"), salted("a"));
+        assert_ne!(salted("a"), salted("b"));
+        assert!(salted("a").starts_with("[sha256:"));
+        assert!(!salted("a").contains("Zk9x"));
+    }
+}
@@ -1,36 +1,153 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Instant;
 
 use anyhow::Result;
 
-use crate::model::{Finding, Report};
+use crate::coverage::CoverageRecorder;
+use crate::examples::{self, ExampleReport, Examples};
+use crate::fingerprint;
+use crate::glob::PathGlobs;
+use crate::model::{CheckTiming, Finding, Location, Report, ScanMetadata, Severity, ToolInfo};
+use crate::redact::Redaction;
+use crate::targets::{self, TextFileCache};
 
 #[derive(Debug, Clone)]
 pub enum ScopeMode {
     Staged,
     Worktree,
     Repo,
+    /// Files and directories given on the command line, read from disk
+    /// without consulting git.
+    Paths(Vec<PathBuf>),
+    /// A single in-memory file (e.g. `-` on the command line), reported as `name`.
+    Stdin {
+        name: String,
+        content: SharedBytes,
+    },
+    /// Lines added by commit `head` relative to commit `base` (usually a merge-base),
+    /// read from git objects without a checkout.
+    Diff {
+        base: String,
+        head: String,
+    },
+    /// Lines added by each commit relative to its first parent, oldest first
+    /// (e.g. the commits a push introduces). Read from git objects only.
+    Commits(Vec<String>),
+}
+
+impl ScopeMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScopeMode::Staged => "staged",
+            ScopeMode::Worktree => "worktree",
+            ScopeMode::Repo => "repo",
+            ScopeMode::Paths(_) => "paths",
+            ScopeMode::Stdin { .. } => "stdin",
+            ScopeMode::Diff { .. } => "diff",
+            ScopeMode::Commits(_) => "commits",
+        }
+    }
+
+    /// Newest commit of a scope read from git objects.
+    pub fn head(&self) -> Option<&str> {
+        match self {
+            ScopeMode::Diff { head, .. } => Some(head),
+            ScopeMode::Commits(commits) => commits.last().map(String::as_str),
+            _ => None,
+        }
+    }
+}
+
+/// Cheaply clonable file content. `Debug` prints only the length, so
+/// scanned content never ends up in logs.
+#[derive(Clone, Default)]
+pub struct SharedBytes(Arc<Vec<u8>>);
+
+impl From<Vec<u8>> for SharedBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(Arc::new(bytes))
+    }
+}
+
+impl Deref for SharedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SharedBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SharedBytes({} bytes)", self.0.len())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Context {
     pub repo_root: PathBuf,
     pub scope: ScopeMode,
+    pub coverage: CoverageRecorder,
+    pub fingerprint_key: Vec<u8>,
+    /// Applied to every matched secret before it is put into a `Finding`.
+    pub redaction: Redaction,
+    /// Paths no check looks at (`[ignore] paths`); see `targets::list_targets`.
+    pub ignore: PathGlobs,
+    /// Target files already loaded in this scan; see `targets::get_target_files`.
+    pub text_files: TextFileCache,
+}
+
+impl Context {
+    pub fn new(repo_root: PathBuf, scope: ScopeMode) -> Self {
+        Self {
+            repo_root,
+            scope,
+            coverage: CoverageRecorder::default(),
+            fingerprint_key: fingerprint::DEFAULT_KEY.to_vec(),
+            redaction: Redaction::default(),
+            ignore: PathGlobs::default(),
+            text_files: TextFileCache::default(),
+        }
+    }
+
+    /// Fingerprint for a finding of `check_id` at `path` whose matched content is `content`.
+    pub fn fingerprint(&self, check_id: &str, path: &str, content: &str) -> String {
+        fingerprint::fingerprint(&self.fingerprint_key, check_id, path, content)
+    }
 }
 
 pub trait Check: Send + Sync {
-    fn id(&self) -> &'static str;
-    fn description(&self) -> &'static str;
+    fn id(&self) -> &str;
+    fn description(&self) -> &str;
     fn run(&self, ctx: &Context) -> Result<Vec<Finding>>;
+
+    /// Samples this check must and must not fire on (built-in plus configured).
+    fn examples(&self) -> Examples {
+        Examples::default()
+    }
+
+    /// Whether the check fires on one sample (see `Examples`); `None` if it
+    /// cannot evaluate samples outside a scan.
+    fn fires_on(&self, _sample: &str) -> Option<bool> {
+        None
+    }
 }
 
 pub struct Runner {
     checks: Vec<Box<dyn Check>>,
+    skips_as_findings: Option<Severity>,
 }
 
 impl Runner {
     pub fn new() -> Self {
-        Self { checks: vec![] }
+        Self {
+            checks: vec![],
+            skips_as_findings: None,
+        }
     }
 
     pub fn with_check(mut self, check: Box<dyn Check>) -> Self {
@@ -38,28 +155,124 @@ impl Runner {
         self
     }
 
+    /// Report every skipped file as a finding with the given severity.
+    pub fn with_skips_as_findings(mut self, severity: Option<Severity>) -> Self {
+        self.skips_as_findings = severity;
+        self
+    }
+
+    /// Ids of the configured checks, in run order.
+    pub fn check_ids(&self) -> Vec<&str> {
+        self.checks.iter().map(|c| c.id()).collect()
+    }
+
+    /// Runs the examples of every check that has some, in run order.
+    pub fn test_examples(&self) -> Vec<ExampleReport> {
+        self.checks
+            .iter()
+            .filter_map(|c| examples::test_examples(c.as_ref()))
+            .collect()
+    }
+
+    /// Describes the scan `run` would perform in `ctx`.
+    pub fn metadata(&self, ctx: &Context, config_hash: Option<String>) -> ScanMetadata {
+        ScanMetadata {
+            tool: ToolInfo {
+                name: "veto".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            repo_root: ctx.repo_root.display().to_string(),
+            commit: targets::head_commit(ctx),
+            scope: ctx.scope.as_str().to_string(),
+            config_hash,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            checks: self.check_ids().iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
     pub fn run(&self, ctx: &Context) -> Result<Report> {
+        self.run_streaming(ctx, |_| Ok(()))
+    }
+
+    /// Like `run`, but hands each check's findings to `on_finding` as soon as
+    /// that check completes, so output can start before the whole scan is done.
+    pub fn run_streaming(
+        &self,
+        ctx: &Context,
+        mut on_finding: impl FnMut(&Finding) -> Result<()>,
+    ) -> Result<Report> {
         let start = Instant::now();
         let mut findings = Vec::new();
+        let mut timings = Vec::new();
+        let mut fingerprints = Fingerprints::default();
 
         for check in &self.checks {
+            let check_start = Instant::now();
             let mut f = check.run(ctx)?;
             // Tag findings with the check id by default (makes filtering easier)
             for item in &mut f {
                 if !item.tags.iter().any(|t| t == check.id()) {
                     item.tags.push(check.id().to_string());
                 }
+                fingerprints.disambiguate(item);
+                on_finding(item)?;
             }
+            timings.push(CheckTiming {
+                id: check.id().to_string(),
+                description: check.description().to_string(),
+                duration_ms: check_start.elapsed().as_millis(),
+                findings: f.len(),
+            });
             findings.extend(f);
         }
 
+        let mut coverage = ctx.coverage.take();
+        coverage.checks = timings;
+
+        if let Some(severity) = self.skips_as_findings {
+            for skipped in &coverage.files_skipped {
+                let mut finding = Finding {
+                    id: "COV-001".to_string(),
+                    fingerprint: ctx.fingerprint("COV-001", &skipped.file, ""),
+                    title: "File not scanned".to_string(),
+                    message: format!("File was skipped ({})", skipped.reason.as_str()),
+                    severity,
+                    location: Some(Location::file(&skipped.file)),
+                    tags: vec!["coverage".to_string()],
+                    snippet: None,
+                    details: None,
+                };
+                fingerprints.disambiguate(&mut finding);
+                on_finding(&finding)?;
+                findings.push(finding);
+            }
+        }
+
         Ok(Report {
             findings,
             duration_ms: start.elapsed().as_millis(),
+            coverage,
         })
     }
 }
 
+/// The same content can match more than once in a file; later occurrences get
+/// a `:2`, `:3`, ... suffix so every finding in a report has its own fingerprint.
+#[derive(Default)]
+struct Fingerprints {
+    seen: HashMap<String, usize>,
+}
+
+impl Fingerprints {
+    fn disambiguate(&mut self, f: &mut Finding) {
+        let count = self.seen.entry(f.fingerprint.clone()).or_insert(0);
+        *count += 1;
+        if *count > 1 {
+            f.fingerprint = format!("{}:{}", f.fingerprint, count);
+        }
+    }
+}
+
 impl Default for Runner {
     fn default() -> Self {
         Self::new()
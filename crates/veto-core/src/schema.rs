@@ -0,0 +1,37 @@
+use schemars::schema_for;
+
+use crate::model::{ReportEnvelope, StreamRecord};
+
+/// JSON Schema of `--format json` output (`docs/schema/report.schema.json`).
+pub fn report_schema() -> String {
+    to_json(&schema_for!(ReportEnvelope))
+}
+
+/// JSON Schema of each line of `--format ndjson` output (`docs/schema/report-stream.schema.json`).
+pub fn stream_schema() -> String {
+    to_json(&schema_for!(StreamRecord))
+}
+
+fn to_json(schema: &schemars::schema::RootSchema) -> String {
+    serde_json::to_string_pretty(schema).expect("schema serializes") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The published schemas are generated by `cargo run -p xtask -- schema`.
+    // If this fails, the report format changed: regenerate the schemas and,
+    // for breaking changes, bump `REPORT_SCHEMA_VERSION`.
+    #[test]
+    fn test_published_schemas_are_up_to_date() {
+        assert_eq!(
+            report_schema(),
+            include_str!("../../../docs/schema/report.schema.json")
+        );
+        assert_eq!(
+            stream_schema(),
+            include_str!("../../../docs/schema/report-stream.schema.json")
+        );
+    }
+}
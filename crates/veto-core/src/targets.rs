@@ -0,0 +1,916 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context as _, Result};
+
+use crate::encoding;
+use crate::model::SkipReason;
+use crate::runner::{Context, ScopeMode, SharedBytes};
+
+/// A file to check: the path findings are reported under, and where its content comes from.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub path: String,
+    pub source: Source,
+    /// Lines to check (1-based, inclusive ranges); `None` means the whole file.
+    pub lines: Option<Vec<(usize, usize)>>,
+}
+
+/// Decoded text of a target, as loaded by `get_target_files`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextFile {
+    pub path: String,
+    pub text: String,
+    pub lines: Option<Vec<(usize, usize)>>,
+}
+
+impl TextFile {
+    /// Whether line `line` (1-based) is in scope, e.g. was added by the diff being scanned.
+    pub fn includes_line(&self, line: usize) -> bool {
+        match &self.lines {
+            None => true,
+            Some(ranges) => ranges.iter().any(|&(s, e)| s <= line && line <= e),
+        }
+    }
+
+    /// Whether any of lines `start..=end` (1-based) is in scope.
+    pub fn includes_lines(&self, start: usize, end: usize) -> bool {
+        match &self.lines {
+            None => true,
+            Some(ranges) => ranges.iter().any(|&(s, e)| s <= end && start <= e),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Source {
+    /// The staged blob (`:path`).
+    Index,
+    /// A file on disk.
+    File(PathBuf),
+    /// Content already in memory (e.g. read from stdin).
+    Memory(SharedBytes),
+    /// A blob in the object database, by id. Needs no worktree.
+    Blob(String),
+}
+
+/// Files added or modified in the current scope, except those matching `ctx.ignore`.
+///
+/// Content is not inspected, so callers see every target regardless of size or type.
+pub fn list_targets(ctx: &Context) -> Result<Vec<Target>> {
+    let mut targets = list_all_targets(ctx)?;
+    targets.retain(|t| !ctx.ignore.is_match(&t.path));
+    Ok(targets)
+}
+
+/// Like `list_targets`, but including ignored paths: for checks that look for
+/// specific files the ignore list hides from content scans (e.g. lockfiles),
+/// or that judge what enters the repository rather than what a file says
+/// (forbidden and large files).
+pub fn list_all_targets(ctx: &Context) -> Result<Vec<Target>> {
+    Ok(match &ctx.scope {
+        ScopeMode::Staged => changed_paths(ctx, true)?
+            .into_iter()
+            .map(|path| Target {
+                path,
+                source: Source::Index,
+                lines: None,
+            })
+            .collect(),
+        ScopeMode::Worktree => changed_paths(ctx, false)?
+            .into_iter()
+            .map(|path| Target {
+                source: Source::File(ctx.repo_root.join(&path)),
+                path,
+                lines: None,
+            })
+            .collect(),
+        ScopeMode::Repo => {
+            // TODO: Phase 2
+            vec![]
+        }
+        ScopeMode::Paths(paths) => {
+            let mut targets = vec![];
+            for path in paths {
+                walk(path, true, &mut targets)?;
+            }
+            // Absolute arguments inside the repo are reported (and matched
+            // against `[ignore]`) like relative ones: `$PWD/a/b` as `a/b`
+            for target in targets.iter_mut() {
+                let path = Path::new(&target.path);
+                if let (true, Ok(relative)) =
+                    (path.is_absolute(), path.strip_prefix(&ctx.repo_root))
+                {
+                    target.path = relative.to_string_lossy().replace('\\', "/");
+                }
+            }
+            targets
+        }
+        ScopeMode::Stdin { name, content } => vec![Target {
+            path: name.clone(),
+            source: Source::Memory(content.clone()),
+            lines: None,
+        }],
+        ScopeMode::Diff { base, head } => diff_targets(ctx, base, head)?,
+        ScopeMode::Commits(commits) => {
+            let mut targets = vec![];
+            let mut seen = HashSet::new();
+            for commit in commits {
+                let parent = match parent(ctx, commit) {
+                    Some(parent) => parent,
+                    None => empty_tree(ctx)?,
+                };
+                for target in diff_targets(ctx, &parent, commit)? {
+                    // The same blob at the same path (e.g. via a merge) is checked once
+                    let Source::Blob(oid) = &target.source else {
+                        continue;
+                    };
+                    if seen.insert((target.path.clone(), oid.clone())) {
+                        targets.push(target);
+                    }
+                }
+            }
+            targets
+        }
+    })
+}
+
+/// Paths of every target, ignored or not (see `list_all_targets`), as they
+/// appear in findings.
+pub fn list_paths(ctx: &Context) -> Result<Vec<String>> {
+    Ok(list_all_targets(ctx)?.into_iter().map(|t| t.path).collect())
+}
+
+/// Paths added or modified relative to the repo root (`git diff [--cached]`).
+fn changed_paths(ctx: &Context, cached: bool) -> Result<Vec<String>> {
+    let mut cmd = Command::new("git");
+    cmd.arg("diff");
+    if cached {
+        cmd.arg("--cached");
+    }
+    let output = cmd
+        .arg("--name-only")
+        .arg("--diff-filter=ACMR")
+        .current_dir(&ctx.repo_root)
+        .output()
+        .context("git diff failed")?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git diff failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let paths = String::from_utf8(output.stdout)?;
+    Ok(paths.lines().map(|p| p.to_string()).collect())
+}
+
+/// Blobs added or modified between commits `base` and `head`, limited to the
+/// lines `head` adds. Everything is read from the object database, so this
+/// works without a checkout (and in bare repositories).
+fn diff_targets(ctx: &Context, base: &str, head: &str) -> Result<Vec<Target>> {
+    let diff = |args: &[&str]| -> Result<Vec<u8>> {
+        let output = Command::new("git")
+            .arg("diff")
+            .args(args)
+            .args(["--no-ext-diff", "--no-textconv", "--no-color", "-M"])
+            .arg("--diff-filter=ACMR")
+            .arg(base)
+            .arg(head)
+            .arg("--")
+            .current_dir(&ctx.repo_root)
+            .output()
+            .context("git diff failed")?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "git diff failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(output.stdout)
+    };
+
+    // -z raw output: :<old mode> <new mode> <old oid> <new oid> <status> NUL <path> NUL [<new path> NUL]
+    let raw = diff(&["--raw", "-z", "--no-abbrev"])?;
+    let mut fields = raw
+        .split(|&b| b == 0)
+        .map(|f| String::from_utf8_lossy(f).into_owned());
+    let mut targets = vec![];
+    while let Some(meta) = fields.next() {
+        let meta: Vec<&str> = meta.trim_start_matches(':').split(' ').collect();
+        let (oid, status) = match meta.as_slice() {
+            [_, _, _, oid, status] => (oid.to_string(), *status),
+            _ => continue,
+        };
+        let mut path = fields.next().unwrap_or_default();
+        if status.starts_with('R') || status.starts_with('C') {
+            path = fields.next().unwrap_or_default();
+        }
+        targets.push(Target {
+            path,
+            source: Source::Blob(oid),
+            // Added files are checked whole; see below for the rest
+            lines: (status != "A").then(Vec::new),
+        });
+    }
+
+    // Added line ranges per file, from the hunk headers of a zero-context patch.
+    // Files appear in the same order as in the raw output, each with a
+    // `diff --git` header (also renames, mode changes and binary files).
+    let patch = diff(&["-U0"])?;
+    let mut index = None;
+    for line in patch.split(|&b| b == b'\n') {
+        if line.starts_with(b"diff --git ") {
+            index = Some(index.map_or(0, |i| i + 1));
+            continue;
+        }
+        let Some(target) = index.and_then(|i| targets.get_mut(i)) else {
+            continue;
+        };
+        if line.starts_with(b"Binary files ") {
+            target.lines = None;
+        } else if let Some(range) = line.strip_prefix(b"@@ ").and_then(added_range) {
+            if let Some(lines) = &mut target.lines {
+                lines.push(range);
+            }
+        }
+    }
+    let files = index.map_or(0, |i| i + 1);
+    anyhow::ensure!(
+        files == targets.len(),
+        "git diff {}..{}: {} patches for {} changed files",
+        base,
+        head,
+        files,
+        targets.len()
+    );
+    Ok(targets)
+}
+
+/// New-side line range of a hunk header (`-a,b +c,d @@`), if it adds any lines.
+fn added_range(header: &[u8]) -> Option<(usize, usize)> {
+    let header = String::from_utf8_lossy(header);
+    let new = header.split(' ').find_map(|part| part.strip_prefix('+'))?;
+    let (start, count) = match new.split_once(',') {
+        Some((start, count)) => (start.parse().ok()?, count.parse().ok()?),
+        None => (new.parse().ok()?, 1),
+    };
+    (count > 0).then(|| (start, start + count - 1))
+}
+
+/// First parent of `commit`, if it has one.
+fn parent(ctx: &Context, commit: &str) -> Option<String> {
+    let out = Command::new("git")
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg(format!("{}^", commit))
+        .current_dir(&ctx.repo_root)
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Id of the empty tree, the base a root commit is diffed against.
+fn empty_tree(ctx: &Context) -> Result<String> {
+    let output = Command::new("git")
+        .arg("hash-object")
+        .arg("-t")
+        .arg("tree")
+        .arg("--stdin")
+        .current_dir(&ctx.repo_root)
+        .stdin(Stdio::null())
+        .output()
+        .context("git hash-object failed")?;
+    if !output.status.success() {
+        anyhow::bail!("git hash-object failed");
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether `oid` is the all-zero id git uses for a missing ref (created or deleted in a push).
+pub fn is_null_oid(oid: &str) -> bool {
+    !oid.is_empty() && oid.bytes().all(|b| b == b'0')
+}
+
+/// Whether `oid` names a commit present in the repository.
+pub fn has_commit(repo_root: &Path, oid: &str) -> bool {
+    Command::new("git")
+        .arg("cat-file")
+        .arg("-e")
+        .arg(format!("{}^{{commit}}", oid))
+        .current_dir(repo_root)
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Commits reachable from `tip` but not from any of `exclude`, oldest first
+/// (`git rev-list --reverse --topo-order <tip> --not <exclude>...`).
+/// `exclude` may contain revisions or options such as `--remotes`/`--all`.
+pub fn new_commits(repo_root: &Path, tip: &str, exclude: &[&str]) -> Result<Vec<String>> {
+    let output = Command::new("git")
+        .arg("rev-list")
+        .arg("--reverse")
+        .arg("--topo-order")
+        .arg(tip)
+        .arg("--not")
+        .args(exclude)
+        .arg("--")
+        .current_dir(repo_root)
+        .output()
+        .context("git rev-list failed")?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git rev-list failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.to_string())
+        .collect())
+}
+
+/// Commit metadata, as read by `read_commit`.
+#[derive(Debug, Clone, Default)]
+pub struct CommitInfo {
+    pub id: String,
+    pub parents: usize,
+    pub author_email: String,
+    pub committer_email: String,
+    pub message: String,
+}
+
+/// Commits the current scope introduces, oldest first. Only scopes read from
+/// git objects have any; staged and worktree changes are not committed yet.
+pub fn scope_commits(ctx: &Context) -> Result<Vec<String>> {
+    match &ctx.scope {
+        ScopeMode::Diff { base, head } => new_commits(&ctx.repo_root, head, &[base.as_str()]),
+        ScopeMode::Commits(commits) => Ok(commits.clone()),
+        _ => Ok(vec![]),
+    }
+}
+
+/// Reads the headers and message of `commit` (`git cat-file commit`).
+pub fn read_commit(ctx: &Context, commit: &str) -> Result<CommitInfo> {
+    let output = Command::new("git")
+        .arg("cat-file")
+        .arg("commit")
+        .arg(commit)
+        .current_dir(&ctx.repo_root)
+        .output()
+        .context("git cat-file failed")?;
+    if !output.status.success() {
+        anyhow::bail!("cannot read commit {}", commit);
+    }
+
+    let raw = String::from_utf8_lossy(&output.stdout);
+    let (headers, message) = raw.split_once("\n\n").unwrap_or((&raw, ""));
+    let mut info = CommitInfo {
+        id: commit.to_string(),
+        message: message.to_string(),
+        ..Default::default()
+    };
+    // Headers: `parent <oid>`, `author Name <email> <time> <tz>`, ...
+    let email = |value: &str| {
+        let start = value.rfind('<').map_or(0, |i| i + 1);
+        let end = value.rfind('>').unwrap_or(value.len()).max(start);
+        value[start..end].to_string()
+    };
+    for header in headers.lines() {
+        match header.split_once(' ') {
+            Some(("parent", _)) => info.parents += 1,
+            Some(("author", value)) => info.author_email = email(value),
+            Some(("committer", value)) => info.committer_email = email(value),
+            _ => {}
+        }
+    }
+    Ok(info)
+}
+
+/// Resolves `rev` to a commit id.
+pub fn resolve_commit(repo_root: &Path, rev: &str) -> Result<String> {
+    let output = Command::new("git")
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg(format!("{}^{{commit}}", rev))
+        .current_dir(repo_root)
+        .output()
+        .context("git rev-parse failed")?;
+    if !output.status.success() {
+        anyhow::bail!("unknown revision {:?}", rev);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Best common ancestor of `base` and `head` (`git merge-base`).
+pub fn merge_base(repo_root: &Path, base: &str, head: &str) -> Result<String> {
+    // Resolved first, so a mistyped revision is reported as such
+    resolve_commit(repo_root, base)?;
+    resolve_commit(repo_root, head)?;
+    let output = Command::new("git")
+        .arg("merge-base")
+        .arg(base)
+        .arg(head)
+        .current_dir(repo_root)
+        .output()
+        .context("git merge-base failed")?;
+    if !output.status.success() {
+        anyhow::bail!("no merge base between {:?} and {:?}", base, head);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Collects `path`, or every file below it in name order. `.git` directories
+/// are skipped. Symlinks are not followed, except `path` itself if `explicit`
+/// (it was named on the command line).
+fn walk(path: &Path, explicit: bool, targets: &mut Vec<Target>) -> Result<()> {
+    let meta = if explicit {
+        fs::metadata(path)
+    } else {
+        fs::symlink_metadata(path)
+    }
+    .with_context(|| format!("cannot read {:?}", path))?;
+    if meta.is_dir() {
+        let mut entries = fs::read_dir(path)
+            .with_context(|| format!("cannot read directory {:?}", path))?
+            .collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            if entry.file_name() != ".git" {
+                walk(&entry.path(), false, targets)?;
+            }
+        }
+    } else if meta.is_file() {
+        let display = path.to_string_lossy().replace('\\', "/");
+        // `veto scan .` should report (and glob-match) `a/b`, not `./a/b`
+        let mut relative = display.as_str();
+        while let Some(rest) = relative.strip_prefix("./") {
+            relative = rest;
+        }
+        targets.push(Target {
+            path: relative.to_string(),
+            source: Source::File(path.to_path_buf()),
+            lines: None,
+        });
+    }
+    Ok(())
+}
+
+/// Loads the text content of every target file, skipping oversized and binary files.
+///
+/// Non-UTF-8 content is decoded (see `encoding::decode`) rather than dropped.
+///
+/// Scanned and skipped files are recorded in `ctx.coverage`. Files are loaded
+/// once per `max_file_bytes` and shared through `ctx.text_files`, so every
+/// check that reads all targets (entropy guard, each `[[rules]]` entry) does
+/// not list and read them again.
+pub fn get_target_files(ctx: &Context, max_file_bytes: u64) -> Result<Arc<Vec<TextFile>>> {
+    let mut cache = ctx
+        .text_files
+        .inner
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(files) = cache.get(&max_file_bytes) {
+        return Ok(files.clone());
+    }
+    let files = Arc::new(load_text_files(ctx, list_targets(ctx)?, max_file_bytes));
+    cache.insert(max_file_bytes, files.clone());
+    Ok(files)
+}
+
+/// Files `get_target_files` has loaded, by `max_file_bytes`. Shared through
+/// `Context` like its coverage; `Debug` prints only the cached limits, so
+/// scanned content never ends up in logs.
+#[derive(Clone, Default)]
+pub struct TextFileCache {
+    inner: Arc<Mutex<HashMap<u64, Arc<Vec<TextFile>>>>>,
+}
+
+impl fmt::Debug for TextFileCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cache = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        write!(f, "TextFileCache({:?})", cache.keys().collect::<Vec<_>>())
+    }
+}
+
+/// Like `get_target_files`, but only targets whose file name is one of
+/// `names`, ignored or not (see `list_all_targets`).
+pub fn get_named_files(
+    ctx: &Context,
+    names: &[&str],
+    max_file_bytes: u64,
+) -> Result<Vec<TextFile>> {
+    let mut targets = list_all_targets(ctx)?;
+    targets.retain(|t| names.contains(&t.path.rsplit('/').next().unwrap_or(&t.path)));
+    Ok(load_text_files(ctx, targets, max_file_bytes))
+}
+
+fn load_text_files(ctx: &Context, targets: Vec<Target>, max_file_bytes: u64) -> Vec<TextFile> {
+    let mut results = vec![];
+
+    for target in targets {
+        // Check size first, without reading the content
+        if let Some(size) = target_size(ctx, &target) {
+            if size > max_file_bytes {
+                ctx.coverage.skipped(&target.path, SkipReason::TooLarge);
+                continue;
+            }
+        }
+
+        // Read max + 1 bytes, in case the file grew after the size check
+        let data = match read_target(ctx, &target, max_file_bytes + 1) {
+            Ok(Some(data)) => data,
+            _ => {
+                ctx.coverage.skipped(&target.path, SkipReason::Unreadable);
+                continue;
+            }
+        };
+        if data.len() as u64 > max_file_bytes {
+            ctx.coverage.skipped(&target.path, SkipReason::TooLarge);
+            continue;
+        }
+
+        if let Some(text) = decode_text(ctx, &target.path, data) {
+            results.push(TextFile {
+                path: target.path,
+                text,
+                lines: target.lines,
+            });
+        }
+    }
+
+    results
+}
+
+/// Turns raw file content into text, recording why it was skipped if that isn't possible.
+fn decode_text(ctx: &Context, path: &str, data: Vec<u8>) -> Option<String> {
+    match encoding::decode(&data) {
+        Some((text, enc)) => {
+            ctx.coverage.scanned(path);
+            ctx.coverage.decoded(path, enc);
+            Some(text)
+        }
+        None => {
+            ctx.coverage.skipped(path, SkipReason::Binary);
+            None
+        }
+    }
+}
+
+/// Size in bytes of the target's content.
+pub fn target_size(ctx: &Context, target: &Target) -> Option<u64> {
+    match &target.source {
+        Source::Index => staged_size(ctx, &target.path),
+        Source::File(path) => fs::metadata(path).ok().map(|m| m.len()),
+        Source::Memory(content) => Some(content.len() as u64),
+        Source::Blob(oid) => object_size(ctx, oid),
+    }
+}
+
+/// Reads at most `limit` bytes from the start of the target's content.
+pub fn read_target(ctx: &Context, target: &Target, limit: u64) -> Result<Option<Vec<u8>>> {
+    let mut buffer = Vec::new();
+    match &target.source {
+        Source::Index | Source::Blob(_) => {
+            let object = match &target.source {
+                Source::Blob(oid) => oid.clone(),
+                _ => format!(":{}", target.path),
+            };
+            // git cat-file blob <object>, without buffering the whole blob
+            let mut child = Command::new("git")
+                .arg("cat-file")
+                .arg("blob")
+                .arg(object)
+                .current_dir(&ctx.repo_root)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .context("git cat-file failed")?;
+            if let Some(stdout) = child.stdout.take() {
+                stdout.take(limit).read_to_end(&mut buffer)?;
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        Source::File(path) => {
+            if !path.is_file() {
+                return Ok(None);
+            }
+            fs::File::open(path)?.take(limit).read_to_end(&mut buffer)?;
+        }
+        Source::Memory(content) => {
+            let end = content.len().min(limit as usize);
+            buffer.extend_from_slice(&content[..end]);
+        }
+    }
+    Ok(Some(buffer))
+}
+
+/// Values of a gitattribute for each path (`git check-attr`), omitting unset/unspecified.
+///
+/// In `Staged` scope, `.gitattributes` is read from the index; in scopes read
+/// from git objects, from the newest commit. Explicit paths and stdin are not tied to a checkout
+/// and have no attributes.
+pub fn attr_values(ctx: &Context, attr: &str, paths: &[String]) -> Result<HashMap<String, String>> {
+    let mut values = HashMap::new();
+    if paths.is_empty() || matches!(ctx.scope, ScopeMode::Paths(_) | ScopeMode::Stdin { .. }) {
+        return Ok(values);
+    }
+
+    let mut cmd = Command::new("git");
+    // Keeps the temporary index alive until check-attr has run
+    let _index;
+    match (&ctx.scope, ctx.scope.head()) {
+        (ScopeMode::Staged, _) => {
+            cmd.arg("check-attr").arg("--cached");
+        }
+        (_, Some(head)) => {
+            let index = TempIndex::read_tree(ctx, head)?;
+            cmd.env("GIT_INDEX_FILE", index.path())
+                .arg("check-attr")
+                .arg("--cached");
+            _index = index;
+        }
+        _ => {
+            cmd.arg("check-attr");
+        }
+    }
+    let output = cmd
+        .arg("-z")
+        .arg(attr)
+        .arg("--")
+        .args(paths)
+        .current_dir(&ctx.repo_root)
+        .output()
+        .context("git check-attr failed")?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git check-attr failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    // -z output: <path> NUL <attribute> NUL <value> NUL
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = stdout.split('\0').collect();
+    for entry in fields.chunks_exact(3) {
+        let (path, value) = (entry[0], entry[2]);
+        if value != "unspecified" && value != "unset" {
+            values.insert(path.to_string(), value.to_string());
+        }
+    }
+    Ok(values)
+}
+
+/// Index file holding the tree of a commit, so attributes can be read without
+/// a checkout (`git check-attr --source` needs a newer git). It lives in a
+/// private temporary directory, removed on drop.
+struct TempIndex {
+    dir: tempfile::TempDir,
+}
+
+impl TempIndex {
+    fn read_tree(ctx: &Context, commit: &str) -> Result<Self> {
+        let index = TempIndex {
+            dir: tempfile::Builder::new()
+                .prefix("veto-index-")
+                .tempdir()
+                .context("cannot create a temporary index")?,
+        };
+        let status = Command::new("git")
+            .env("GIT_INDEX_FILE", index.path())
+            .arg("read-tree")
+            .arg(commit)
+            .current_dir(&ctx.repo_root)
+            .stderr(Stdio::null())
+            .status()
+            .context("git read-tree failed")?;
+        if !status.success() {
+            anyhow::bail!("git read-tree {} failed", commit);
+        }
+        Ok(index)
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir.path().join("index")
+    }
+}
+
+/// Commit being scanned: the newest scanned commit, or `HEAD` if the repo has one.
+pub fn head_commit(ctx: &Context) -> Option<String> {
+    if let Some(head) = ctx.scope.head() {
+        return Some(head.to_string());
+    }
+    let out = Command::new("git")
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg("HEAD")
+        .current_dir(&ctx.repo_root)
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// Size of the staged blob for `path` (`git cat-file -s :path`).
+fn staged_size(ctx: &Context, path: &str) -> Option<u64> {
+    object_size(ctx, &format!(":{}", path))
+}
+
+/// Size of a blob in the object database (`git cat-file -s <object>`).
+fn object_size(ctx: &Context, object: &str) -> Option<u64> {
+    let out = Command::new("git")
+        .arg("cat-file")
+        .arg("-s")
+        .arg(object)
+        .current_dir(&ctx.repo_root)
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&out.stdout).trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glob::PathGlobs;
+    use crate::testutil::TestRepo;
+
+    #[test]
+    fn test_paths_and_stdin_targets() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join(".git/config"), "x").unwrap();
+        fs::write(dir.join("sub/b.txt"), "b").unwrap();
+        fs::write(dir.join("a.txt"), "a").unwrap();
+        fs::create_dir_all(dir.join("sub/vendor/x")).unwrap();
+        fs::write(dir.join("sub/vendor/x/c.txt"), "c").unwrap();
+        fs::write(dir.join("sub/go.sum"), "d").unwrap();
+
+        let mut ctx = Context::new(Default::default(), ScopeMode::Paths(vec![dir.clone()]));
+        ctx.ignore = PathGlobs::new(&["**/vendor/**".into(), "go.sum".into()]).unwrap();
+        assert_eq!(list_all_targets(&ctx).unwrap().len(), 4);
+        let files = get_target_files(&ctx, 10).unwrap();
+        let names: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        let root = dir.to_string_lossy().replace('\\', "/");
+        assert_eq!(
+            names,
+            vec![format!("{}/a.txt", root), format!("{}/sub/b.txt", root)]
+        );
+
+        // Inside the repo, absolute paths are made relative to its root
+        let mut ctx = Context::new(
+            dir.clone(),
+            ScopeMode::Paths(vec![dir.join("sub"), dir.join("a.txt")]),
+        );
+        ctx.ignore = PathGlobs::new(&["sub/vendor/**".into(), "go.sum".into()]).unwrap();
+        let names: Vec<String> = list_targets(&ctx)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.path)
+            .collect();
+        assert_eq!(names, vec!["sub/b.txt", "a.txt"]);
+
+        let ctx = Context::new(
+            Default::default(),
+            ScopeMode::Stdin {
+                name: "<stdin>".into(),
+                content: SharedBytes::from(b"hello".to_vec()),
+            },
+        );
+        let files = get_target_files(&ctx, 10).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            (files[0].path.as_str(), files[0].text.as_str()),
+            ("<stdin>", "hello")
+        );
+        assert!(get_target_files(&ctx, 3).unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_explicit_symlinks_are_followed() {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("a.txt"), "a").unwrap();
+        fs::create_dir(root.join("sub")).unwrap();
+        symlink(root.join("a.txt"), root.join("link.txt")).unwrap();
+        symlink(root.join("a.txt"), root.join("sub/nested.txt")).unwrap();
+
+        let ctx = Context::new(
+            Default::default(),
+            ScopeMode::Paths(vec![root.join("link.txt"), root.join("sub")]),
+        );
+        let files = get_target_files(&ctx, 10).unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].path.ends_with("/link.txt"));
+        assert_eq!(files[0].text, "a");
+
+        let ctx = Context::new(
+            Default::default(),
+            ScopeMode::Paths(vec![root.join("missing.txt")]),
+        );
+        assert!(list_all_targets(&ctx).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_diff_targets() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let repo = TestRepo::new();
+        let numbered = |n: usize| (1..=n).map(|i| format!("line {}\n", i)).collect::<String>();
+        repo.write("keep.txt", numbered(5));
+        repo.write("moved.txt", numbered(20));
+        repo.write("edited.txt", numbered(20));
+        repo.write("blob.bin", b"\x00\x01\x02");
+        repo.write("run.sh", "echo hi\n");
+        let base = repo.commit("base");
+
+        repo.write("keep.txt", numbered(5).replace("line 3", "changed"));
+        repo.git(&["mv", "moved.txt", "renamed.txt"]);
+        repo.git(&["mv", "edited.txt", "renamed-edited.txt"]);
+        repo.write("renamed-edited.txt", numbered(20) + "extra\n");
+        repo.write("blob.bin", b"\x00\x01\x03");
+        let script = repo.path().join("run.sh");
+        fs::set_permissions(script, fs::Permissions::from_mode(0o755)).unwrap();
+        repo.write("new.txt", "a\nb\n");
+        let head = repo.commit("head");
+
+        let ctx = Context::new(
+            repo.path().to_path_buf(),
+            ScopeMode::Diff {
+                base: base.clone(),
+                head: head.clone(),
+            },
+        );
+        let mut targets: Vec<_> = list_all_targets(&ctx)
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.path, t.lines))
+            .collect();
+        targets.sort();
+        assert_eq!(
+            targets,
+            vec![
+                ("blob.bin".to_string(), None),
+                ("keep.txt".to_string(), Some(vec![(3, 3)])),
+                ("new.txt".to_string(), None),
+                ("renamed-edited.txt".to_string(), Some(vec![(21, 21)])),
+                // Pure rename
+                ("renamed.txt".to_string(), Some(vec![])),
+                // Mode change only
+                ("run.sh".to_string(), Some(vec![])),
+            ]
+        );
+
+        let err = merge_base(repo.path(), "no-such-branch", &head).unwrap_err();
+        assert_eq!(err.to_string(), "unknown revision \"no-such-branch\"");
+        assert_eq!(merge_base(repo.path(), &base, &head).unwrap(), base);
+    }
+
+    #[test]
+    fn test_attr_values_in_bare_repository() {
+        let repo = TestRepo::new();
+        repo.write(".gitattributes", "*.psd filter=lfs\n");
+        let head = repo.commit("attributes");
+        let bare = tempfile::tempdir().unwrap();
+        let bare_path = bare.path().to_string_lossy().to_string();
+        repo.git(&["clone", "-q", "--bare", ".", &bare_path]);
+
+        let ctx = Context::new(bare.path().to_path_buf(), ScopeMode::Commits(vec![head]));
+        let paths = vec!["art/a.psd".to_string(), "b.txt".to_string()];
+        let values = attr_values(&ctx, "filter", &paths).unwrap();
+        assert_eq!(values.len(), 1);
+        assert_eq!(values["art/a.psd"], "lfs");
+    }
+
+    #[test]
+    fn test_added_range() {
+        assert_eq!(added_range(b"-3,2 +4,3 @@ fn x"), Some((4, 6)));
+        assert_eq!(added_range(b"-3 +7 @@"), Some((7, 7)));
+        assert_eq!(added_range(b"-3,2 +2,0 @@"), None);
+    }
+}
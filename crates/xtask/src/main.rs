@@ -1,6 +1,42 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
 use anyhow::Result;
 
 fn main() -> Result<()> {
-    println!("xtask placeholder (add release helpers here)");
+    match std::env::args().nth(1).as_deref() {
+        Some("schema") => write_schemas(),
+        _ => {
+            println!("usage: cargo run -p xtask -- <task>");
+            println!();
+            println!("tasks:");
+            println!("  schema   regenerate docs/schema/*.schema.json from veto-core types");
+            Ok(())
+        }
+    }
+}
+
+fn write_schemas() -> Result<()> {
+    let dir = workspace_root().join("docs/schema");
+    fs::create_dir_all(&dir)?;
+    for (name, schema) in [
+        ("report.schema.json", veto_core::schema::report_schema()),
+        (
+            "report-stream.schema.json",
+            veto_core::schema::stream_schema(),
+        ),
+    ] {
+        let path = dir.join(name);
+        fs::write(&path, schema)?;
+        println!("wrote {}", path.display());
+    }
     Ok(())
 }
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .ancestors()
+        .nth(2)
+        .expect("xtask lives in crates/xtask")
+        .to_path_buf()
+}